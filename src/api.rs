@@ -19,6 +19,12 @@ pub struct Application {
     pub deployment_url: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Application this one is a preview of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_app_id: Option<uuid::Uuid>,
+    /// Git branch a preview application was deployed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_branch: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -29,6 +35,97 @@ pub struct Deployment {
     pub status: String,
     pub artifact_url: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Progress of a canary or blue-green deployment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollout: Option<Rollout>,
+}
+
+impl Deployment {
+    /// Whether the platform is still starting the deployment.
+    pub fn is_starting(&self) -> bool {
+        matches!(
+            self.status.to_lowercase().as_str(),
+            "pending" | "queued" | "building" | "deploying" | "starting"
+        )
+    }
+
+    /// Whether the deployment is up and serving traffic.
+    pub fn is_running(&self) -> bool {
+        matches!(
+            self.status.to_lowercase().as_str(),
+            "running" | "active" | "healthy" | "deployed"
+        )
+    }
+
+    /// Whether the deployment came up successfully, whether or not it has
+    /// been replaced since.
+    pub fn has_run(&self) -> bool {
+        self.is_running()
+            || matches!(
+                self.status.to_lowercase().as_str(),
+                "stopped" | "superseded" | "inactive" | "succeeded"
+            )
+    }
+}
+
+/// How traffic moves from the running deployment to a new one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum RolloutStrategy {
+    /// Replace the running deployment at once
+    #[default]
+    AllAtOnce,
+    /// Shift traffic to the new deployment in steps
+    Canary,
+    /// Start the new deployment alongside the running one, then switch all
+    /// traffic to it
+    BlueGreen,
+}
+
+impl RolloutStrategy {
+    pub fn is_all_at_once(&self) -> bool {
+        *self == RolloutStrategy::AllAtOnce
+    }
+}
+
+/// Progress of a canary or blue-green deployment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rollout {
+    pub strategy: RolloutStrategy,
+    /// Share of traffic the new deployment receives
+    pub traffic_percent: u8,
+    /// Traffic percentages of a canary deployment's steps
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<u8>,
+    pub state: RolloutState,
+}
+
+impl Rollout {
+    /// Traffic the new deployment receives after the next promotion.
+    pub fn next_percent(&self) -> u8 {
+        self.steps
+            .iter()
+            .copied()
+            .find(|&percent| percent > self.traffic_percent)
+            .unwrap_or(100)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RolloutState {
+    /// Traffic is still split, waiting to be promoted or aborted
+    InProgress,
+    /// The new deployment serves all traffic
+    Promoted,
+    /// Traffic went back to the previous deployment
+    Aborted,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RollbackRequest {
+    /// Deployment to make current again
+    pub deployment_id: uuid::Uuid,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,13 +133,197 @@ pub struct CreateAppRequest {
     pub name: String,
     pub description: Option<String>,
     pub runtime: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_command: Option<String>,
+    /// Application this one is a preview of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_app_id: Option<uuid::Uuid>,
+    /// Git branch a preview application is deployed from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_branch: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 pub struct DeployRequest {
     pub app_id: uuid::Uuid,
     pub version: String,
     pub artifact_url: String,
+    /// Hex SHA-256 of the artifact, checked by the platform before it is
+    /// unpacked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_log_url: Option<String>,
+    #[serde(skip_serializing_if = "ArtifactType::is_tarball")]
+    pub artifact_type: ArtifactType,
+    /// Compression of tarball artifacts
+    #[serde(skip_serializing_if = "CompressionFormat::is_gzip")]
+    pub compression: CompressionFormat,
+    /// Installed dependencies, unpacked before the application artifact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency_layer: Option<DependencyLayerRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub static_site: Option<StaticSiteConfig>,
+    #[serde(skip_serializing_if = "RolloutStrategy::is_all_at_once")]
+    pub strategy: RolloutStrategy,
+    /// Traffic percentages a canary deployment goes through, ending at 100
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub canary_steps: Vec<u8>,
+}
+
+/// What a deployment artifact contains.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactType {
+    /// Application files packaged for one of the language runtimes
+    #[default]
+    Tarball,
+    /// OCI image archive built from a Dockerfile
+    OciImage,
+}
+
+impl ArtifactType {
+    pub fn is_tarball(&self) -> bool {
+        *self == ArtifactType::Tarball
+    }
+
+    /// File extension of the artifact; tarballs take theirs from
+    /// `compression`.
+    pub fn extension(self, compression: CompressionFormat) -> &'static str {
+        match self {
+            ArtifactType::Tarball => compression.extension(),
+            ArtifactType::OciImage => "oci.tar",
+        }
+    }
+
+    /// Content type of the artifact; tarballs take theirs from
+    /// `compression`.
+    pub fn content_type(self, compression: CompressionFormat) -> &'static str {
+        match self {
+            ArtifactType::Tarball => compression.content_type(),
+            ArtifactType::OciImage => "application/vnd.oci.image.layout.v1+tar",
+        }
+    }
+}
+
+/// A dependency layer stored by the platform, keyed by
+/// [`crate::builder::ProjectBuilder::dependency_layer_key`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyLayerRef {
+    pub key: String,
+    pub artifact_url: String,
+    /// Hex SHA-256 of the layer tarball
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// An artifact stored by the platform, identified by the SHA-256 of its
+/// content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredArtifact {
+    pub artifact_url: String,
+    /// Hex SHA-256 of the artifact
+    pub sha256: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Compression applied to tarball artifacts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionFormat {
+    #[default]
+    Gzip,
+    /// Zstandard, compressed on all CPU cores
+    Zstd,
+}
+
+impl CompressionFormat {
+    /// The format of an existing tarball, judged by its file name.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        let name = path.to_string_lossy();
+        if name.ends_with(".zst") || name.ends_with(".tzst") {
+            CompressionFormat::Zstd
+        } else {
+            CompressionFormat::Gzip
+        }
+    }
+
+    pub fn is_gzip(&self) -> bool {
+        *self == CompressionFormat::Gzip
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CompressionFormat::Gzip => "gzip",
+            CompressionFormat::Zstd => "zstd",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            CompressionFormat::Gzip => "tar.gz",
+            CompressionFormat::Zstd => "tar.zst",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            CompressionFormat::Gzip => "application/gzip",
+            CompressionFormat::Zstd => "application/zstd",
+        }
+    }
+
+    pub fn default_level(self) -> i32 {
+        match self {
+            CompressionFormat::Gzip => 6,
+            CompressionFormat::Zstd => 3,
+        }
+    }
+
+    /// Levels accepted by `--compression-level`.
+    pub fn levels(self) -> std::ops::RangeInclusive<i32> {
+        match self {
+            CompressionFormat::Gzip => 0..=9,
+            CompressionFormat::Zstd => 1..=22,
+        }
+    }
+}
+
+/// How the platform serves a static site deployment.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StaticSiteConfig {
+    /// File served for paths matching no file, e.g. `index.html` for
+    /// single-page apps
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spa_fallback: Option<String>,
+    pub cache: Vec<CacheRule>,
+    pub redirects: Vec<RedirectRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheRule {
+    /// Path pattern, e.g. `/assets/*`
+    pub source: String,
+    /// `Cache-Control` header value for matching files
+    pub cache_control: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RedirectRule {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "default_redirect_status")]
+    pub status: u16,
+}
+
+fn default_redirect_status() -> u16 {
+    301
 }
 
 // Authentication models
@@ -99,6 +380,10 @@ pub struct GeneratePresignedUrlRequest {
     pub app_id: uuid::Uuid,
     pub version: String,
     pub filename: String,
+    /// Base64 SHA-256 of the file, signed into the upload URL
+    pub checksum_sha256: String,
+    /// Base64 MD5 of the file, signed into the upload URL
+    pub content_md5: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -108,6 +393,86 @@ pub struct GeneratePresignedUrlResponse {
     pub expires_in: u64,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GeneratePresignedDownloadUrlRequest {
+    pub app_id: uuid::Uuid,
+    pub s3_key: String,
+}
+
+/// A presigned GET URL for a stored artifact.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactDownload {
+    pub download_url: String,
+    /// Seconds until the URL expires
+    pub expires_in: u64,
+    /// Hex SHA-256 of the artifact, when the platform recorded it
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+// Multipart upload models
+#[derive(Debug, Serialize)]
+pub struct CreateMultipartUploadRequest {
+    pub app_id: uuid::Uuid,
+    pub version: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size: u64,
+    pub part_count: u32,
+    /// Hex SHA-256 of the whole file
+    pub sha256: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMultipartUploadResponse {
+    pub upload_id: String,
+    pub s3_key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MultipartPartUrlsRequest {
+    pub s3_key: String,
+    pub parts: Vec<MultipartPartRequest>,
+}
+
+/// A part to presign an upload URL for, with the checksums signed into it.
+#[derive(Debug, Serialize)]
+pub struct MultipartPartRequest {
+    pub part_number: u32,
+    pub checksum_sha256: String,
+    pub content_md5: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultipartPartUrlsResponse {
+    pub parts: Vec<MultipartPartUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultipartPartUrl {
+    pub part_number: u32,
+    pub upload_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompleteMultipartUploadRequest {
+    pub s3_key: String,
+    pub parts: Vec<CompletedPart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedPart {
+    pub part_number: u32,
+    pub etag: String,
+    /// Base64 SHA-256 of the part
+    pub checksum_sha256: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AbortMultipartUploadRequest {
+    pub s3_key: String,
+}
+
 pub struct ApiClient {
     client: Client,
     base_url: String,
@@ -154,6 +519,19 @@ impl ApiClient {
         }
     }
 
+    /// Like `handle_response` for endpoints that return no body.
+    async fn handle_empty_response(&self, response: Response) -> Result<()> {
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(AetherError::Api {
+                status: status.as_u16(),
+                message: response.text().await?,
+            })
+        }
+    }
+
     pub async fn health_check(&self) -> Result<()> {
         let url = format!("{}/health", self.base_url);
         let response = self.client.get(&url).send().await?;
@@ -233,21 +611,13 @@ impl ApiClient {
         self.handle_response(response).await
     }
 
-    pub async fn deploy_application(
-        &self,
-        app_id: uuid::Uuid,
-        version: String,
-        artifact_url: String,
-    ) -> Result<Deployment> {
-        let url = format!("{}/api/v1/apps/{}/deployments", self.base_url, app_id);
-
-        // Create JSON payload with just S3 URL - backend will generate presigned URL
-        let deploy_request = DeployRequest {
-            app_id,
-            version,
-            artifact_url,
-        };
+    pub async fn deploy_application(&self, deploy_request: DeployRequest) -> Result<Deployment> {
+        let url = format!(
+            "{}/api/v1/apps/{}/deployments",
+            self.base_url, deploy_request.app_id
+        );
 
+        // JSON payload carries just the S3 URL - backend will generate presigned URL
         let mut req = self.client.post(&url).json(&deploy_request);
 
         if let Some(ref token) = self.auth_token {
@@ -284,6 +654,87 @@ impl ApiClient {
         self.handle_response(response).await
     }
 
+    pub async fn get_deployment(
+        &self,
+        app_id: uuid::Uuid,
+        deployment_id: uuid::Uuid,
+    ) -> Result<Deployment> {
+        let url = format!(
+            "{}/api/v1/apps/{}/deployments/{}",
+            self.base_url, app_id, deployment_id
+        );
+        let mut req = self.client.get(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Move an in-progress rollout to its next step: the next canary
+    /// percentage, or for blue-green deployments all traffic.
+    pub async fn promote_deployment(
+        &self,
+        app_id: uuid::Uuid,
+        deployment_id: uuid::Uuid,
+    ) -> Result<Deployment> {
+        let url = format!(
+            "{}/api/v1/apps/{}/deployments/{}/promote",
+            self.base_url, app_id, deployment_id
+        );
+        let mut req = self.client.post(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Stop an in-progress rollout, sending all traffic back to the
+    /// previous deployment.
+    pub async fn abort_deployment(
+        &self,
+        app_id: uuid::Uuid,
+        deployment_id: uuid::Uuid,
+    ) -> Result<Deployment> {
+        let url = format!(
+            "{}/api/v1/apps/{}/deployments/{}/abort",
+            self.base_url, app_id, deployment_id
+        );
+        let mut req = self.client.post(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Make an earlier deployment of the application current again.
+    pub async fn rollback_application(
+        &self,
+        app_id: uuid::Uuid,
+        deployment_id: uuid::Uuid,
+    ) -> Result<Deployment> {
+        let url = format!("{}/api/v1/apps/{}/rollback", self.base_url, app_id);
+        let mut req = self
+            .client
+            .post(&url)
+            .json(&RollbackRequest { deployment_id });
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
     pub async fn monitor_deployment(&self, app_id: uuid::Uuid) -> Result<Vec<String>> {
         let url = format!("{}/api/v1/apps/{}/monitor", self.base_url, app_id);
 
@@ -455,21 +906,126 @@ impl ApiClient {
         self.handle_response(response).await
     }
 
+    /// The dependency layer stored for `key` by an earlier deployment of the
+    /// application, if the platform still has it.
+    pub async fn find_dependency_layer(
+        &self,
+        app_id: uuid::Uuid,
+        key: &str,
+    ) -> Result<Option<DependencyLayerRef>> {
+        let url = format!("{}/api/v1/apps/{}/layers/{}", self.base_url, app_id, key);
+        let mut req = self.client.get(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        self.handle_response(response).await.map(Some)
+    }
+
+    /// The artifact with content hash `sha256` stored by an earlier upload
+    /// for the application, if the platform still has it.
+    pub async fn find_artifact(
+        &self,
+        app_id: uuid::Uuid,
+        sha256: &str,
+    ) -> Result<Option<StoredArtifact>> {
+        let url = format!(
+            "{}/api/v1/apps/{}/artifacts/{}",
+            self.base_url, app_id, sha256
+        );
+        let mut req = self.client.get(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        self.handle_response(response).await.map(Some)
+    }
+
+    /// All artifacts the platform stores for the application.
+    pub async fn list_artifacts(&self, app_id: uuid::Uuid) -> Result<Vec<StoredArtifact>> {
+        let url = format!("{}/api/v1/apps/{}/artifacts", self.base_url, app_id);
+        let mut req = self.client.get(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
+    /// Delete the stored artifact with content hash `sha256`.
+    pub async fn delete_artifact(&self, app_id: uuid::Uuid, sha256: &str) -> Result<()> {
+        let url = format!(
+            "{}/api/v1/apps/{}/artifacts/{}",
+            self.base_url, app_id, sha256
+        );
+        let mut req = self.client.delete(&url);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_empty_response(response).await
+    }
+
     // Presigned URL methods
     pub async fn get_presigned_upload_url(
         &self,
-        app_id: uuid::Uuid,
-        version: &str,
-        filename: &str,
+        request: GeneratePresignedUrlRequest,
     ) -> Result<GeneratePresignedUrlResponse> {
         let url = format!("{}/api/v1/uploads/presigned-url", self.base_url);
-        let request_body = GeneratePresignedUrlRequest {
-            app_id,
-            version: version.to_string(),
-            filename: filename.to_string(),
-        };
+        let mut req = self.client.post(&url).json(&request);
 
-        let mut req = self.client.post(&url).json(&request_body);
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_response(response).await
+    }
+
+    /// A presigned URL to download the stored file `s3_key`, or `None` when
+    /// the platform does not serve downloads.
+    pub async fn get_presigned_download_url(
+        &self,
+        request: GeneratePresignedDownloadUrlRequest,
+    ) -> Result<Option<ArtifactDownload>> {
+        let url = format!("{}/api/v1/uploads/presigned-download-url", self.base_url);
+        let mut req = self.client.post(&url).json(&request);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        if matches!(
+            response.status(),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Ok(None);
+        }
+        self.handle_response(response).await.map(Some)
+    }
+
+    /// Start a multipart upload of a file split into `part_count` parts.
+    pub async fn create_multipart_upload(
+        &self,
+        request: CreateMultipartUploadRequest,
+    ) -> Result<CreateMultipartUploadResponse> {
+        let url = format!("{}/api/v1/uploads/multipart", self.base_url);
+        let mut req = self.client.post(&url).json(&request);
 
         if let Some(ref token) = self.auth_token {
             req = req.bearer_auth(token);
@@ -478,4 +1034,65 @@ impl ApiClient {
         let response = req.send().await?;
         self.handle_response(response).await
     }
+
+    /// Presigned PUT URLs for the given parts of a multipart upload.
+    pub async fn get_multipart_part_urls(
+        &self,
+        upload_id: &str,
+        request: MultipartPartUrlsRequest,
+    ) -> Result<Vec<MultipartPartUrl>> {
+        let url = format!(
+            "{}/api/v1/uploads/multipart/{}/parts",
+            self.base_url, upload_id
+        );
+        let mut req = self.client.post(&url).json(&request);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        let response: MultipartPartUrlsResponse = self.handle_response(response).await?;
+        Ok(response.parts)
+    }
+
+    /// Assemble the uploaded parts into the final object.
+    pub async fn complete_multipart_upload(
+        &self,
+        upload_id: &str,
+        request: CompleteMultipartUploadRequest,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/api/v1/uploads/multipart/{}/complete",
+            self.base_url, upload_id
+        );
+        let mut req = self.client.post(&url).json(&request);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_empty_response(response).await
+    }
+
+    /// Abort a multipart upload, discarding the parts uploaded so far.
+    pub async fn abort_multipart_upload(
+        &self,
+        upload_id: &str,
+        request: AbortMultipartUploadRequest,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/api/v1/uploads/multipart/{}/abort",
+            self.base_url, upload_id
+        );
+        let mut req = self.client.post(&url).json(&request);
+
+        if let Some(ref token) = self.auth_token {
+            req = req.bearer_auth(token);
+        }
+
+        let response = req.send().await?;
+        self.handle_empty_response(response).await
+    }
 }
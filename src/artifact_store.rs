@@ -0,0 +1,540 @@
+//! Storage deployment artifacts are uploaded to.
+//!
+//! Every store uploads with the same progress reporting, checksums and
+//! return types; [`open`] picks the one the configuration selects.
+
+use crate::api::{
+    ApiClient, ArtifactDownload, ArtifactType, CompressionFormat, DependencyLayerRef,
+    StoredArtifact,
+};
+use crate::config::{ArtifactStoreConfig, Config};
+use crate::presigned_uploader::PresignedUploader;
+use crate::s3_uploader::S3Uploader;
+use crate::upload::{self, ChecksumHasher, Checksums, OutputCallback, ProgressCallback};
+use crate::Result;
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// A file stored by an upload.
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    /// Key of the file in storage
+    pub key: String,
+    /// Hex SHA-256 of the stored content
+    pub sha256: String,
+    /// Whether identical content was already stored, so nothing was uploaded
+    pub reused: bool,
+}
+
+pub trait ArtifactStore: Send + Sync {
+    /// Where files are stored, for messages, e.g. "S3 bucket 'artifacts'".
+    fn describe(&self) -> String;
+
+    /// Upload a deployment artifact, or reuse identical content the store
+    /// already holds for the application.
+    fn upload_artifact<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        format: ArtifactFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>>;
+
+    /// The dependency layer stored under `layer_key` by an earlier deploy
+    /// of the application, if the store still has it.
+    fn find_dependency_layer<'a>(
+        &'a self,
+        app_id: Uuid,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<Option<DependencyLayerRef>>>;
+
+    /// Upload a dependency layer stored under `layer_key`.
+    fn upload_dependency_layer<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>>;
+
+    /// Upload a build log next to the deployment artifact.
+    fn upload_build_log<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<UploadedFile>>;
+
+    /// A URL to download the stored file `key` from, or `None` when the
+    /// store cannot provide one.
+    fn download_url<'a>(
+        &'a self,
+        app_id: Uuid,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ArtifactDownload>>>;
+
+    /// The artifacts stored for the application, in no particular order.
+    fn list_artifacts(&self, app_id: Uuid) -> BoxFuture<'_, Result<Vec<StoredArtifact>>>;
+
+    /// Delete a stored artifact returned by [`ArtifactStore::list_artifacts`].
+    fn delete_artifact<'a>(
+        &'a self,
+        app_id: Uuid,
+        artifact: &'a StoredArtifact,
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// Kind and compression of an artifact, which decide its file extension
+/// and content type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArtifactFormat {
+    pub artifact_type: ArtifactType,
+    pub compression: CompressionFormat,
+}
+
+impl ArtifactFormat {
+    pub fn extension(self) -> &'static str {
+        self.artifact_type.extension(self.compression)
+    }
+
+    pub fn content_type(self) -> &'static str {
+        self.artifact_type.content_type(self.compression)
+    }
+}
+
+/// Open the artifact store selected by `config`, reporting the bytes sent
+/// by each upload to `progress` and messages, such as an upload being
+/// resumed, to `output` (stdout if unset).
+pub async fn open(
+    config: &Config,
+    client: ApiClient,
+    progress: Option<ProgressCallback>,
+    output: Option<OutputCallback>,
+) -> Result<Box<dyn ArtifactStore>> {
+    Ok(match &config.artifact_store {
+        ArtifactStoreConfig::Presigned => {
+            let mut store = PresignedUploader::new(client);
+            if let Some(progress) = progress {
+                store = store.with_progress(progress);
+            }
+            if let Some(output) = output {
+                store = store.with_output_callback(output);
+            }
+            Box::new(store)
+        }
+        ArtifactStoreConfig::S3 {
+            bucket,
+            region,
+            endpoint,
+        } => {
+            let mut store = S3Uploader::new(bucket, region.as_deref(), endpoint.as_deref()).await?;
+            if let Some(progress) = progress {
+                store = store.with_progress(progress);
+            }
+            Box::new(store)
+        }
+        ArtifactStoreConfig::Local { path } => {
+            let mut store = LocalStore::new(path);
+            if let Some(progress) = progress {
+                store = store.with_progress(progress);
+            }
+            Box::new(store)
+        }
+    })
+}
+
+/// Key of a dependency layer in stores that name files themselves. Layers
+/// are shared by every version built from the same lockfile.
+pub(crate) fn dependency_layer_key(
+    app_id: Uuid,
+    layer_key: &str,
+    compression: CompressionFormat,
+) -> String {
+    format!(
+        "artifacts/{}/layers/deps-{}.{}",
+        app_id,
+        layer_key,
+        compression.extension()
+    )
+}
+
+/// Whether the deployment artifact at `url` is `artifact`.
+pub fn is_same_artifact(artifact: &StoredArtifact, url: &str) -> bool {
+    url == artifact.artifact_url || url.contains(&artifact.sha256)
+}
+
+/// Split `artifacts` into those used by the `deployed` artifact URLs plus
+/// the `keep` newest others, and the rest, which can be pruned. Both lists
+/// are newest first.
+pub fn retention(
+    mut artifacts: Vec<StoredArtifact>,
+    keep: usize,
+    deployed: &[&str],
+) -> (Vec<StoredArtifact>, Vec<StoredArtifact>) {
+    artifacts.sort_by_key(|artifact| std::cmp::Reverse(artifact.created_at));
+    let mut kept = Vec::new();
+    let mut pruned = Vec::new();
+    let mut undeployed_kept = 0;
+    for artifact in artifacts {
+        if deployed.iter().any(|url| is_same_artifact(&artifact, url)) {
+            kept.push(artifact);
+        } else if undeployed_kept < keep {
+            undeployed_kept += 1;
+            kept.push(artifact);
+        } else {
+            pruned.push(artifact);
+        }
+    }
+    (kept, pruned)
+}
+
+/// Stores files in a local directory. Artifacts are named by their SHA-256,
+/// so unchanged artifacts are reused.
+pub struct LocalStore {
+    root: PathBuf,
+    progress: Option<ProgressCallback>,
+}
+
+impl LocalStore {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            progress: None,
+        }
+    }
+
+    /// Report the bytes copied by each upload to `callback`.
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    /// Copy `path`, whose content has `checksums`, to `key` under the
+    /// store's root, or reuse the file already there when `reuse_stored` is
+    /// set.
+    async fn store(
+        &self,
+        path: &Path,
+        checksums: Checksums,
+        key: &str,
+        reuse_stored: bool,
+    ) -> Result<UploadedFile> {
+        let dest = self.root.join(key);
+        let url = format!("file://{}", dest.display());
+        if reuse_stored && dest.is_file() {
+            return Ok(UploadedFile {
+                key: url,
+                sha256: checksums.sha256_hex(),
+                reused: true,
+            });
+        }
+
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        // Copy then rename, so an interruption never leaves a torn file
+        let mut tmp_name = dest.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+        let (_, chunks) = upload::file_stream(path, self.progress.clone()).await?;
+        let mut chunks = Box::pin(chunks);
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", tmp_path.display(), e))?;
+        let mut hasher = ChecksumHasher::default();
+        while let Some(chunk) = chunks.try_next().await? {
+            hasher.update(&chunk);
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+
+        let copied = hasher.finish();
+        if copied != checksums {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            return Err(anyhow::anyhow!(
+                "{} changed while it was being copied (SHA-256 {} before, {} copied)",
+                path.display(),
+                checksums.sha256_hex(),
+                copied.sha256_hex()
+            )
+            .into());
+        }
+        tokio::fs::rename(&tmp_path, &dest).await?;
+
+        Ok(UploadedFile {
+            key: url,
+            sha256: checksums.sha256_hex(),
+            reused: false,
+        })
+    }
+}
+
+impl ArtifactStore for LocalStore {
+    fn describe(&self) -> String {
+        format!("directory {}", self.root.display())
+    }
+
+    fn upload_artifact<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        _version: &'a str,
+        format: ArtifactFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let checksums = Checksums::of_file(path).await?;
+            let key = format!(
+                "artifacts/{}/{}.{}",
+                app_id,
+                checksums.sha256_hex(),
+                format.extension()
+            );
+            self.store(path, checksums, &key, true).await
+        })
+    }
+
+    fn find_dependency_layer<'a>(
+        &'a self,
+        app_id: Uuid,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<Option<DependencyLayerRef>>> {
+        Box::pin(async move {
+            let path = self
+                .root
+                .join(dependency_layer_key(app_id, layer_key, compression));
+            Ok(path.is_file().then(|| DependencyLayerRef {
+                key: layer_key.to_string(),
+                artifact_url: format!("file://{}", path.display()),
+                sha256: None,
+            }))
+        })
+    }
+
+    fn upload_dependency_layer<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        _version: &'a str,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let key = dependency_layer_key(app_id, layer_key, compression);
+            let checksums = Checksums::of_file(path).await?;
+            self.store(path, checksums, &key, false).await
+        })
+    }
+
+    fn upload_build_log<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let key = format!("artifacts/{}/{}/build.log", app_id, version);
+            let checksums = Checksums::of_file(path).await?;
+            self.store(path, checksums, &key, false).await
+        })
+    }
+
+    fn download_url<'a>(
+        &'a self,
+        _app_id: Uuid,
+        _key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ArtifactDownload>>> {
+        // Stored files are read straight from the directory
+        Box::pin(async { Ok(None) })
+    }
+
+    fn list_artifacts(&self, app_id: Uuid) -> BoxFuture<'_, Result<Vec<StoredArtifact>>> {
+        Box::pin(async move {
+            let dir = self.root.join("artifacts").join(app_id.to_string());
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            };
+
+            // Artifacts sit directly in the app's directory, named
+            // `<sha256>.<ext>`; layers and logs are in version directories
+            let mut artifacts = Vec::new();
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !metadata.is_file() || name.ends_with(".tmp") {
+                    continue;
+                }
+                let Some((sha256, _)) = name.split_once('.') else {
+                    continue;
+                };
+                artifacts.push(StoredArtifact {
+                    artifact_url: format!("file://{}", entry.path().display()),
+                    sha256: sha256.to_string(),
+                    size: metadata.len(),
+                    version: None,
+                    created_at: metadata.modified().ok().map(Into::into),
+                });
+            }
+            Ok(artifacts)
+        })
+    }
+
+    fn delete_artifact<'a>(
+        &'a self,
+        _app_id: Uuid,
+        artifact: &'a StoredArtifact,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let path = artifact
+                .artifact_url
+                .strip_prefix("file://")
+                .unwrap_or(&artifact.artifact_url);
+            tokio::fs::remove_file(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to delete {}: {}", path, e).into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_format_follows_compression() {
+        let format = |artifact_type, compression| ArtifactFormat {
+            artifact_type,
+            compression,
+        };
+        let zstd = format(ArtifactType::Tarball, CompressionFormat::Zstd);
+        assert_eq!(zstd.extension(), "tar.zst");
+        assert_eq!(zstd.content_type(), "application/zstd");
+        let gzip = format(ArtifactType::Tarball, CompressionFormat::Gzip);
+        assert_eq!(gzip.extension(), "tar.gz");
+        assert_eq!(gzip.content_type(), "application/gzip");
+        let image = format(ArtifactType::OciImage, CompressionFormat::Zstd);
+        assert_eq!(image.extension(), "oci.tar");
+    }
+
+    #[tokio::test]
+    async fn test_local_store_reuses_unchanged_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let artifact = dir.path().join("app.tar.gz");
+        std::fs::write(&artifact, b"artifact").unwrap();
+        let store = LocalStore::new(dir.path().join("store"));
+        let format = ArtifactFormat {
+            artifact_type: ArtifactType::Tarball,
+            compression: CompressionFormat::Gzip,
+        };
+
+        let app_id = Uuid::nil();
+        let first = store
+            .upload_artifact(&artifact, app_id, "1.0.0", format)
+            .await
+            .unwrap();
+        assert!(!first.reused);
+        let stored = first.key.strip_prefix("file://").unwrap();
+        assert_eq!(std::fs::read(stored).unwrap(), b"artifact");
+
+        let second = store
+            .upload_artifact(&artifact, app_id, "1.0.1", format)
+            .await
+            .unwrap();
+        assert!(second.reused);
+        assert_eq!(second.key, first.key);
+        assert_eq!(second.sha256, first.sha256);
+
+        let listed = store.list_artifacts(app_id).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].sha256, first.sha256);
+        assert_eq!(listed[0].artifact_url, first.key);
+        store.delete_artifact(app_id, &listed[0]).await.unwrap();
+        assert!(store.list_artifacts(app_id).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_local_store_finds_dependency_layers() {
+        let dir = tempfile::tempdir().unwrap();
+        let layer = dir.path().join("deps.tar.gz");
+        std::fs::write(&layer, b"node_modules").unwrap();
+        let store = LocalStore::new(dir.path().join("store"));
+        let app_id = Uuid::nil();
+        let gzip = CompressionFormat::Gzip;
+
+        assert!(store
+            .find_dependency_layer(app_id, "abc123", gzip)
+            .await
+            .unwrap()
+            .is_none());
+        let uploaded = store
+            .upload_dependency_layer(&layer, app_id, "1.0.0", "abc123", gzip)
+            .await
+            .unwrap();
+
+        // Found by later versions, without asking the platform
+        let found = store
+            .find_dependency_layer(app_id, "abc123", gzip)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.key, "abc123");
+        assert_eq!(found.artifact_url, uploaded.key);
+        assert!(store
+            .find_dependency_layer(app_id, "abc123", CompressionFormat::Zstd)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(store
+            .find_dependency_layer(app_id, "def456", gzip)
+            .await
+            .unwrap()
+            .is_none());
+        // Layers are not listed as artifacts
+        assert!(store.list_artifacts(app_id).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_and_deployed_artifacts() {
+        let artifact = |sha256: &str, age_days: i64| StoredArtifact {
+            artifact_url: format!("artifacts/app/{}.tar.gz", sha256),
+            sha256: sha256.to_string(),
+            size: 1,
+            version: None,
+            created_at: Some(chrono::Utc::now() - chrono::Duration::days(age_days)),
+        };
+        let artifacts = vec![
+            artifact("old", 30),
+            artifact("newest", 1),
+            artifact("deployed", 20),
+            artifact("newer", 10),
+        ];
+
+        let (kept, pruned) = retention(artifacts, 2, &["artifacts/app/deployed.tar.gz"]);
+        let shas = |list: &[StoredArtifact]| -> Vec<String> {
+            list.iter()
+                .map(|artifact| artifact.sha256.clone())
+                .collect()
+        };
+        assert_eq!(shas(&kept), ["newest", "newer", "deployed"]);
+        assert_eq!(shas(&pruned), ["old"]);
+
+        // A deployed artifact does not take up one of the kept slots
+        let artifacts = vec![
+            artifact("old", 30),
+            artifact("previous", 10),
+            artifact("deployed", 1),
+        ];
+        let (kept, pruned) = retention(artifacts, 1, &["artifacts/app/deployed.tar.gz"]);
+        assert_eq!(shas(&kept), ["deployed", "previous"]);
+        assert_eq!(shas(&pruned), ["old"]);
+    }
+}
@@ -1,64 +1,176 @@
-use crate::{AetherError, Result};
-use flate2::write::GzEncoder;
-use flate2::Compression;
+use crate::api::{ArtifactType, CompressionFormat};
+use crate::manifest::{ArtifactManifest, GitInfo, LockfileInfo, MANIFEST_FILE};
+use crate::platform::{self, Platform};
+use crate::runtime::{self, ContainerRuntime, NodeRuntime, Runtime};
+use crate::{
+    project_config::{HealthCheckConfig, ProjectConfig},
+    AetherError, Result,
+};
+use flate2::{Compression, GzBuilder};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde::Deserialize;
-use std::fs::File;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
 use tar::Builder as TarBuilder;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
 
-#[derive(Debug, Deserialize)]
-pub struct PackageJson {
-    pub name: String,
-    pub version: Option<String>,
-    pub scripts: Option<std::collections::HashMap<String, String>>,
-    pub dependencies: Option<std::collections::HashMap<String, String>>,
-    pub engines: Option<Engines>,
+pub struct ProjectBuilder {
+    project_path: PathBuf,
+    runtime: Box<dyn Runtime>,
+    project_config: ProjectConfig,
+    clean: bool,
+    reproducible: bool,
+    compression: CompressionFormat,
+    compression_level: Option<i32>,
+    cached_dependency_layer: bool,
+    target_platform: Option<Platform>,
+    skip_build: bool,
+    verbose: bool,
+    build_log: PathBuf,
+    build_timeout: Duration,
+    /// Timestamp given to every archive entry, in seconds since the epoch
+    source_date_epoch: u64,
+    output_callback: Option<Box<dyn Fn(&str) + Send + Sync>>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct Engines {
-    pub node: Option<String>,
-    pub npm: Option<String>,
+/// Artifacts produced by [`ProjectBuilder::build_layers`].
+pub struct BuildArtifacts {
+    /// Application files, or the whole project when it has no dependency
+    /// layer
+    pub app: PathBuf,
+    pub dependency_layer: Option<DependencyLayer>,
 }
 
-pub struct ProjectBuilder {
-    project_path: PathBuf,
-    package_json: PackageJson,
-    output_callback: Option<Box<dyn Fn(&str) + Send + Sync>>,
+/// Installed dependencies packaged apart from the application.
+pub struct DependencyLayer {
+    /// See [`ProjectBuilder::dependency_layer_key`]
+    pub key: String,
+    /// `None` when the layer was not packaged because the platform
+    /// already has it
+    pub path: Option<PathBuf>,
+}
+
+/// Number of trailing output lines included in build error messages.
+const OUTPUT_TAIL_LINES: usize = 20;
+
+/// Source date used when `SOURCE_DATE_EPOCH` is not set and the project has
+/// no commits: 1980-01-01, the earliest date zip-based tooling can represent.
+const DEFAULT_SOURCE_DATE_EPOCH: u64 = 315_532_800;
+
+/// Build tools and staging directories that must be cleaned up if the CLI
+/// is interrupted mid-build (see [`cancel_active_builds`]).
+static ACTIVE_BUILD_PROCESSES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static ACTIVE_STAGING_DIRS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Entries of the project root never copied into a clean build's staging
+/// directory.
+pub(crate) const STAGING_EXCLUDES: &[&str] = &[
+    "node_modules",
+    ".git",
+    ".aether",
+    "target",
+    ".venv",
+    "__pycache__",
+    ".deno",
+];
+
+/// Temporary directory used for clean builds, removed when dropped.
+pub(crate) struct StagingDir {
+    path: PathBuf,
+}
+
+impl StagingDir {
+    pub(crate) fn create(app_name: &str) -> Result<Self> {
+        let path = std::env::temp_dir().join(format!(
+            "aether-build-{}-{}",
+            app_name.replace('/', "-"),
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&path)?;
+        if let Ok(mut dirs) = ACTIVE_STAGING_DIRS.lock() {
+            dirs.push(path.clone());
+        }
+        Ok(Self { path })
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+        if let Ok(mut dirs) = ACTIVE_STAGING_DIRS.lock() {
+            dirs.retain(|dir| dir != &self.path);
+        }
+    }
 }
 
 impl ProjectBuilder {
+    /// Create a builder for the project in `project_path`, detecting its
+    /// runtime from the files it contains.
     pub fn new<P: AsRef<Path>>(project_path: P) -> Result<Self> {
         let project_path = project_path.as_ref().to_path_buf();
-        let package_json_path = project_path.join("package.json");
+        let runtime = runtime::detect(&project_path)?;
+        Self::with_runtime(project_path, runtime)
+    }
 
-        if !package_json_path.exists() {
-            return Err(AetherError::invalid_project(
-                "No package.json found in project directory",
-            ));
-        }
+    /// Build a single package of the npm/yarn/pnpm workspace containing
+    /// `start_dir`, bundling its local workspace dependencies.
+    pub fn for_workspace<P: AsRef<Path>>(start_dir: P, package_name: &str) -> Result<Self> {
+        let runtime = NodeRuntime::for_workspace(start_dir.as_ref(), package_name)?;
+        let project_path = runtime.project_path().to_path_buf();
+        Self::with_runtime(project_path, Box::new(runtime))
+    }
 
-        let package_json_content = std::fs::read_to_string(&package_json_path)?;
-        let package_json: PackageJson = serde_json::from_str(&package_json_content)?;
+    /// Build an OCI image from `dockerfile` (relative to `project_path`)
+    /// instead of packaging the project for its runtime.
+    pub fn for_dockerfile<P: AsRef<Path>>(project_path: P, dockerfile: &Path) -> Result<Self> {
+        let project_path = project_path.as_ref().to_path_buf();
+        let runtime = ContainerRuntime::new(&project_path, dockerfile)?;
+        Self::with_runtime(project_path, Box::new(runtime))
+    }
+
+    fn with_runtime(project_path: PathBuf, runtime: Box<dyn Runtime>) -> Result<Self> {
+        let project_config = ProjectConfig::load(&project_path)?;
+        let build_log = default_build_log_path(runtime.app_name());
+        let source_date_epoch = source_date_epoch_from_env()
+            .or_else(|| last_commit_time(&project_path))
+            .unwrap_or(DEFAULT_SOURCE_DATE_EPOCH);
 
         Ok(Self {
             project_path,
-            package_json,
+            runtime,
+            project_config,
+            clean: false,
+            reproducible: false,
+            compression: CompressionFormat::default(),
+            compression_level: None,
+            cached_dependency_layer: false,
+            target_platform: None,
+            skip_build: false,
+            verbose: false,
+            build_log,
+            build_timeout: Duration::from_secs(crate::config::Config::default().build_timeout),
+            source_date_epoch,
             output_callback: None,
         })
     }
 
     pub fn get_app_name(&self) -> &str {
-        &self.package_json.name
+        self.runtime.app_name()
     }
 
     pub fn get_version(&self) -> String {
-        self.package_json
-            .version
-            .clone()
-            .unwrap_or_else(|| "1.0.0".to_string())
+        self.runtime.version().unwrap_or("1.0.0").to_string()
     }
 
     pub fn with_output_callback<F>(mut self, callback: F) -> Self
@@ -69,7 +181,98 @@ impl ProjectBuilder {
         self
     }
 
-    fn output(&self, message: &str) {
+    /// Build in an isolated staging directory with a frozen-lockfile install
+    /// instead of reusing the working tree and its installed dependencies.
+    pub fn with_clean_build(mut self, clean: bool) -> Self {
+        self.clean = clean;
+        self
+    }
+
+    /// Guarantee the same sources and lockfile always produce a
+    /// byte-identical artifact: implies a clean build, and passes
+    /// `SOURCE_DATE_EPOCH` (the last commit's time unless already set) to
+    /// build tools.
+    pub fn with_reproducible(mut self, reproducible: bool) -> Self {
+        self.reproducible = reproducible;
+        self
+    }
+
+    /// Compress the tarball artifact with `format` at `level`, or the
+    /// format's default level. Ignored for OCI image artifacts.
+    pub fn with_compression(mut self, format: CompressionFormat, level: Option<i32>) -> Self {
+        self.compression = format;
+        self.compression_level = level;
+        self
+    }
+
+    /// Leave the dependency layer out of [`Self::build_layers`] because the
+    /// platform already has it from an earlier deployment.
+    pub fn with_cached_dependency_layer(mut self, cached: bool) -> Self {
+        self.cached_dependency_layer = cached;
+        self
+    }
+
+    /// Install dependencies for `platform` instead of the machine running
+    /// the build, and fail if native modules built for another platform
+    /// end up in the artifact. Cross-installs run as clean builds so
+    /// binaries already installed for the host are not reused.
+    pub fn with_target_platform(mut self, platform: Option<Platform>) -> Self {
+        self.target_platform = platform;
+        self
+    }
+
+    /// Package the project as-is, skipping the build script and any
+    /// configured pre/post-build commands.
+    pub fn with_skip_build(mut self, skip_build: bool) -> Self {
+        self.skip_build = skip_build;
+        self
+    }
+
+    /// Print every line of build tool output instead of only the latest one.
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Write the full build tool output to `path` instead of the default
+    /// location under `~/.aether/logs`.
+    pub fn with_build_log<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.build_log = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Abort the build, killing any running build tools, once it has run
+    /// for longer than `timeout`.
+    pub fn with_build_timeout(mut self, timeout: Duration) -> Self {
+        self.build_timeout = timeout;
+        self
+    }
+
+    pub fn build_log_path(&self) -> &Path {
+        &self.build_log
+    }
+
+    pub(crate) fn is_clean(&self) -> bool {
+        self.clean || self.reproducible || self.cross_install_platform().is_some()
+    }
+
+    /// Platform native modules in the artifact must be built for.
+    pub fn target_platform(&self) -> Platform {
+        self.target_platform.unwrap_or(Platform::RUNTIME)
+    }
+
+    /// The requested target platform when it differs from the host, so
+    /// dependencies have to be installed for another platform.
+    pub(crate) fn cross_install_platform(&self) -> Option<Platform> {
+        self.target_platform
+            .filter(|&platform| Some(platform) != Platform::host())
+    }
+
+    pub(crate) fn skips_build(&self) -> bool {
+        self.skip_build
+    }
+
+    pub(crate) fn output(&self, message: &str) {
         if let Some(ref callback) = self.output_callback {
             callback(message);
         } else {
@@ -77,179 +280,404 @@ impl ProjectBuilder {
         }
     }
 
-    pub fn get_node_version(&self) -> String {
-        self.package_json
-            .engines
-            .as_ref()
-            .and_then(|e| e.node.as_deref())
-            .unwrap_or("20")
-            .to_string()
+    pub fn runtime(&self) -> &dyn Runtime {
+        self.runtime.as_ref()
     }
 
     pub fn detect_runtime(&self) -> String {
-        let node_version = self.get_node_version();
-        // Extract major version number
-        if let Some(major) = node_version
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>()
-            .parse::<u32>()
-            .ok()
-        {
-            format!("node:{}", major)
-        } else {
-            "node:20".to_string()
+        self.runtime.runtime_id()
+    }
+
+    pub fn artifact_type(&self) -> ArtifactType {
+        self.runtime.artifact_type()
+    }
+
+    pub fn compression(&self) -> CompressionFormat {
+        self.compression
+    }
+
+    /// File extension of the artifact, e.g. `tar.zst`.
+    pub fn artifact_extension(&self) -> &'static str {
+        self.artifact_type().extension(self.compression)
+    }
+
+    /// Key identifying the dependency layer: a hash of the lockfile and of
+    /// everything else that changes what gets installed. `None` when the
+    /// runtime has no dependency directories or the project no lockfile.
+    pub fn dependency_layer_key(&self) -> Option<String> {
+        if self.runtime.dependency_dirs().is_empty() || !self.artifact_type().is_tarball() {
+            return None;
+        }
+        let lockfile = LockfileInfo::find(self.runtime.source_root()).ok()??;
+
+        let mut hasher = Sha256::new();
+        // Native modules are installed for the host unless cross-installing
+        let platform = match self.target_platform {
+            Some(platform) => platform.to_string(),
+            None => format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        };
+        for part in [
+            lockfile.sha256.as_str(),
+            &self.detect_runtime(),
+            &platform,
+            self.compression.extension(),
+        ] {
+            hasher.update(part);
+            hasher.update([0]);
+        }
+        Some(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Command the platform runs to start the application: `start` from
+    /// aether.json, or the one detected for the runtime.
+    pub fn start_command(&self) -> Option<String> {
+        self.project_config
+            .start
+            .clone()
+            .or_else(|| self.runtime.start_command())
+    }
+
+    /// Health checks configured in aether.json.
+    pub fn health_check(&self) -> Option<&HealthCheckConfig> {
+        self.project_config.health_check.as_ref()
+    }
+
+    /// File the start command runs, which must end up in the artifact.
+    pub fn entry_file(&self) -> Option<String> {
+        match &self.project_config.start {
+            Some(command) => runtime::entry_file_in_command(command),
+            None => self.runtime.entry_file(),
         }
     }
 
     pub async fn build(&self, output_path: Option<PathBuf>) -> Result<PathBuf> {
-        self.output("🔧 Building NodeJS application...");
+        Ok(self.build_artifacts(output_path, false).await?.app)
+    }
 
-        // Install dependencies
-        self.install_dependencies().await?;
+    /// Build the project, packaging its installed dependencies as a
+    /// separate layer next to the application artifact when it has a
+    /// [`Self::dependency_layer_key`].
+    pub async fn build_layers(&self, output_path: Option<PathBuf>) -> Result<BuildArtifacts> {
+        self.build_artifacts(output_path, true).await
+    }
 
-        // Run build script if available
-        self.run_build_script().await?;
+    async fn build_artifacts(
+        &self,
+        output_path: Option<PathBuf>,
+        layered: bool,
+    ) -> Result<BuildArtifacts> {
+        self.output(&format!(
+            "🔧 Building {} application...",
+            self.runtime.display_name()
+        ));
+        self.start_build_log()?;
 
-        // Create artifact
         let artifact_path = output_path.unwrap_or_else(|| {
-            std::env::temp_dir().join(format!("{}.tar.gz", self.get_app_name()))
+            std::env::temp_dir().join(format!(
+                "{}.{}",
+                self.get_app_name(),
+                self.artifact_extension()
+            ))
         });
 
-        self.create_artifact(&artifact_path).await?;
+        let layer_key = if layered {
+            self.dependency_layer_key()
+        } else {
+            None
+        };
+
+        // Dropping the in-flight build kills its child process tree and
+        // removes any staging directory
+
+        let artifacts = tokio::select! {
+            result = self.run_build(&artifact_path, layer_key) => result?,
+            _ = tokio::time::sleep(self.build_timeout) => {
+                return Err(AetherError::BuildTimeout {
+                    timeout_secs: self.build_timeout.as_secs(),
+                    log: self.build_log.clone(),
+                });
+            }
+        };
 
         self.output(&format!("✅ Build completed: {}", artifact_path.display()));
-        Ok(artifact_path)
+        self.output(&format!("📝 Build log: {}", self.build_log.display()));
+        if self.reproducible {
+            self.output(&format!(
+                "🔒 Artifact SHA-256: {}",
+                crate::utils::sha256_file(&artifact_path)?
+            ));
+            if let Some(path) = artifacts
+                .dependency_layer
+                .as_ref()
+                .and_then(|layer| layer.path.as_ref())
+            {
+                self.output(&format!(
+                    "🔒 Dependency layer SHA-256: {}",
+                    crate::utils::sha256_file(path)?
+                ));
+            }
+        }
+        Ok(artifacts)
     }
 
-    async fn install_dependencies(&self) -> Result<()> {
-        self.output("📦 Installing dependencies...");
+    async fn run_build(
+        &self,
+        artifact_path: &Path,
+        layer_key: Option<String>,
+    ) -> Result<BuildArtifacts> {
+        // Clean builds work on a copy of the sources
+        let staging = if self.is_clean() {
+            let staging = StagingDir::create(self.get_app_name())?;
+            self.output(&format!(
+                "🧹 Clean build in staging directory: {}",
+                staging.path().display()
+            ));
+            let (source, destination) = (
+                self.runtime.source_root().to_path_buf(),
+                staging.path().to_path_buf(),
+            );
+            run_blocking(move || copy_dir_excluding(&source, &destination, STAGING_EXCLUDES))
+                .await?;
+            Some(staging)
+        } else {
+            None
+        };
+        let root = staging
+            .as_ref()
+            .map(|staging| staging.path())
+            .unwrap_or(self.runtime.source_root());
 
-        // Check if node_modules exists and has content
-        let node_modules = self.project_path.join("node_modules");
-        if node_modules.exists() && std::fs::read_dir(&node_modules)?.count() > 0 {
-            self.output("📦 Dependencies already installed, skipping...");
-            return Ok(());
+        let package_dir = self.runtime.build(self, root).await?;
+        let single = BuildArtifacts {
+            app: artifact_path.to_path_buf(),
+            dependency_layer: None,
+        };
+        if package_dir.is_archive() {
+            std::fs::copy(package_dir.path(), artifact_path)?;
+            return Ok(single);
         }
 
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.set_message("Installing dependencies...");
-        pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        // Create artifact
+        let mut paths = self.runtime.artifact_paths(package_dir.path())?;
+        if let Some(entry) = self.entry_file() {
+            self.include_entry_file(package_dir.path(), &entry, &mut paths)?;
+        }
+        let entries = archive_entries(package_dir.path(), &paths)?;
+        self.check_native_binaries(package_dir.path(), &entries)?;
 
-        // Determine package manager
-        let package_manager = self.detect_package_manager();
+        let dependency_dirs = self.runtime.dependency_dirs();
+        let (dependencies, application): (Vec<_>, Vec<_>) =
+            entries.iter().cloned().partition(|entry| {
+                entry.components().next().is_some_and(|first| {
+                    dependency_dirs.iter().any(|dir| first.as_os_str() == *dir)
+                })
+            });
+        let Some(key) = layer_key.filter(|_| !dependencies.is_empty()) else {
+            self.create_artifact(package_dir.path(), entries, artifact_path, None)
+                .await?;
+            return Ok(single);
+        };
 
-        let mut cmd = Command::new(&package_manager);
-        cmd.current_dir(&self.project_path);
+        let layer_path = if self.cached_dependency_layer {
+            self.output(&format!(
+                "♻️  Dependency layer {} unchanged, reusing it",
+                &key[..12]
+            ));
+            None
+        } else {
+            let path = artifact_path.with_file_name(format!(
+                "{}-deps.{}",
+                self.get_app_name(),
+                self.artifact_extension()
+            ));
+            self.create_artifact(package_dir.path(), dependencies, &path, Some(&key))
+                .await?;
+            Some(path)
+        };
+        self.create_artifact(package_dir.path(), application, artifact_path, Some(&key))
+            .await?;
 
-        match package_manager.as_str() {
-            "npm" => {
-                cmd.args(&["install", "--production"]);
-            }
-            "yarn" => {
-                cmd.args(&["install", "--production"]);
-            }
-            "pnpm" => {
-                cmd.args(&["install", "--prod"]);
-            }
-            _ => {
-                cmd.args(&["install", "--production"]);
+        Ok(BuildArtifacts {
+            app: artifact_path.to_path_buf(),
+            dependency_layer: Some(DependencyLayer {
+                key,
+                path: layer_path,
+            }),
+        })
+    }
+
+    /// Check the built entry file exists and add it to the artifact when it
+    /// lives outside the runtime's usual source directories.
+    fn include_entry_file(
+        &self,
+        package_dir: &Path,
+        entry: &str,
+        paths: &mut Vec<PathBuf>,
+    ) -> Result<()> {
+        let entry_path = Path::new(entry);
+        if !package_dir.join(entry_path).is_file() {
+            return Err(AetherError::build(format!(
+                "Entry file '{}' not found after the build; check the start command or set \"start\" in aether.json",
+                entry
+            )));
+        }
+
+        if !paths.iter().any(|path| entry_path.starts_with(path)) {
+            if let Some(top_level) = entry_path.components().next() {
+                paths.push(PathBuf::from(top_level.as_os_str()));
             }
         }
 
-        let output = cmd.output()?;
-        pb.finish_and_clear();
+        self.output(&format!("🏁 Entry file: {}", entry));
+        Ok(())
+    }
+
+    /// Warn about native modules that will not load on the target
+    /// platform, or fail when a target platform was requested explicitly.
+    fn check_native_binaries(&self, package_dir: &Path, entries: &[PathBuf]) -> Result<()> {
+        let target = self.target_platform();
+        let foreign = platform::find_foreign_binaries(package_dir, entries, target)?;
+        if foreign.is_empty() {
+            return Ok(());
+        }
+
+        let mut list: Vec<String> = foreign
+            .iter()
+            .take(5)
+            .map(|binary| {
+                let platforms: Vec<String> =
+                    binary.platforms.iter().map(|p| p.to_string()).collect();
+                format!("   {} ({})", binary.path.display(), platforms.join(", "))
+            })
+            .collect();
+        if foreign.len() > 5 {
+            list.push(format!("   ... and {} more", foreign.len() - 5));
+        }
 
-        if !output.status.success() {
-            let error = String::from_utf8_lossy(&output.stderr);
+        if self.target_platform.is_some() {
             return Err(AetherError::build(format!(
-                "Failed to install dependencies: {}",
-                error
+                "Native modules not built for {}:\n{}",
+                target,
+                list.join("\n")
             )));
         }
-
-        self.output("✅ Dependencies installed successfully");
+        self.output(&format!(
+            "⚠️  {} native module(s) were not built for {} and will fail to load:\n{}\n   Reinstall them for the runtime with --target-platform {}",
+            foreign.len(),
+            target,
+            list.join("\n"),
+            target
+        ));
         Ok(())
     }
 
-    fn detect_package_manager(&self) -> String {
-        // Check for lock files to determine package manager
-        if self.project_path.join("yarn.lock").exists() {
-            "yarn".to_string()
-        } else if self.project_path.join("pnpm-lock.yaml").exists() {
-            "pnpm".to_string()
-        } else {
-            "npm".to_string()
+    fn start_build_log(&self) -> Result<()> {
+        if let Some(parent) = self.build_log.parent() {
+            std::fs::create_dir_all(parent)?;
         }
+        let mut log = File::create(&self.build_log)?;
+        writeln!(
+            log,
+            "# aether build log for {} {} ({})",
+            self.get_app_name(),
+            self.get_version(),
+            chrono::Utc::now().to_rfc3339()
+        )?;
+        Ok(())
     }
 
-    async fn run_build_script(&self) -> Result<()> {
-        let scripts = match &self.package_json.scripts {
-            Some(scripts) => scripts,
-            None => return Ok(()), // No scripts defined
-        };
+    /// Run a build tool, streaming its output line by line to the spinner
+    /// (or the output callback) and appending it to the build log.
+    pub(crate) async fn run_command(
+        &self,
+        mut cmd: Command,
+        message: &str,
+        failure: &str,
+    ) -> Result<()> {
+        let pb = self.spinner(message);
 
-        // Check for common build script names
-        let build_script = scripts
-            .get("build")
-            .or_else(|| scripts.get("compile"))
-            .or_else(|| scripts.get("prepare"));
+        let command_line = std::iter::once(cmd.as_std().get_program())
+            .chain(cmd.as_std().get_args())
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
 
-        if let Some(_script) = build_script {
-            println!("🏗️  Running build script...");
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.build_log)?;
+        writeln!(log, "\n$ {}", command_line)?;
 
-            let pb = ProgressBar::new_spinner();
-            pb.set_style(
-                ProgressStyle::default_spinner()
-                    .template("{spinner:.green} {msg}")
-                    .unwrap(),
-            );
-            pb.set_message("Building application...");
-            pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        if self.reproducible {
+            cmd.env("SOURCE_DATE_EPOCH", self.source_date_epoch.to_string());
+        }
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Run in its own process group so the whole tree can be killed
+        #[cfg(unix)]
+        cmd.process_group(0);
 
-            let package_manager = self.detect_package_manager();
-            let mut cmd = Command::new(&package_manager);
-            cmd.current_dir(&self.project_path);
+        let mut child = ChildGuard::spawn(&mut cmd).map_err(|e| {
+            pb.finish_and_clear();
+            AetherError::build(format!(
+                "{}: could not run `{}`: {}",
+                failure, command_line, e
+            ))
+        })?;
 
-            match package_manager.as_str() {
-                "npm" => {
-                    cmd.args(&["run", "build"]);
-                }
-                "yarn" => {
-                    cmd.args(&["run", "build"]);
-                }
-                "pnpm" => {
-                    cmd.args(&["run", "build"]);
-                }
-                _ => {
-                    cmd.args(&["run", "build"]);
-                }
-            }
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.0.stdout.take() {
+            spawn_line_reader(stdout, tx.clone());
+        }
+        if let Some(stderr) = child.0.stderr.take() {
+            spawn_line_reader(stderr, tx.clone());
+        }
+        drop(tx);
 
-            let output = cmd.output()?;
-            pb.finish_and_clear();
+        let mut tail = VecDeque::with_capacity(OUTPUT_TAIL_LINES);
+        while let Some(line) = rx.recv().await {
+            writeln!(log, "{}", line)?;
+            self.build_output(&pb, message, &line);
 
-            if !output.status.success() {
-                let error = String::from_utf8_lossy(&output.stderr);
-                return Err(AetherError::build(format!(
-                    "Build script failed: {}",
-                    error
-                )));
+            if tail.len() == OUTPUT_TAIL_LINES {
+                tail.pop_front();
             }
+            tail.push_back(line);
+        }
 
-            self.output("✅ Build script completed successfully");
+        let status = child.0.wait().await?;
+        pb.finish_and_clear();
+
+        if !status.success() {
+            writeln!(log, "# {}", status)?;
+            let tail: Vec<String> = tail.into();
+            return Err(AetherError::build(format!(
+                "{} ({})\n{}\nFull build log: {}",
+                failure,
+                status,
+                tail.join("\n"),
+                self.build_log.display()
+            )));
         }
 
         Ok(())
     }
 
-    async fn create_artifact(&self, output_path: &Path) -> Result<()> {
-        self.output("📦 Creating deployment artifact...");
+    fn build_output(&self, pb: &ProgressBar, message: &str, line: &str) {
+        if self.verbose || self.output_callback.is_some() {
+            pb.suspend(|| self.output(line));
+        } else {
+            let latest: String = line.trim().chars().take(80).collect();
+            pb.set_message(format!("{} {}", message, latest));
+        }
+    }
+
+    fn spinner(&self, message: &str) -> ProgressBar {
+        // Callers that capture output (e.g. the dashboard) draw their own UI
+        if self.output_callback.is_some() {
+            return ProgressBar::hidden();
+        }
 
         let pb = ProgressBar::new_spinner();
         pb.set_style(
@@ -257,85 +685,775 @@ impl ProjectBuilder {
                 .template("{spinner:.green} {msg}")
                 .unwrap(),
         );
-        pb.set_message("Packaging application...");
+        pb.set_message(message.to_string());
         pb.enable_steady_tick(std::time::Duration::from_millis(100));
+        pb
+    }
 
-        let tar_gz = File::create(output_path)?;
-        let enc = GzEncoder::new(tar_gz, Compression::default());
-        let mut tar = TarBuilder::new(enc);
-
-        // Add essential files and directories
-        self.add_to_archive(&mut tar, "package.json")?;
-
-        // Add package-lock.json or yarn.lock if they exist
-        if self.project_path.join("package-lock.json").exists() {
-            self.add_to_archive(&mut tar, "package-lock.json")?;
-        }
-        if self.project_path.join("yarn.lock").exists() {
-            self.add_to_archive(&mut tar, "yarn.lock")?;
-        }
-        if self.project_path.join("pnpm-lock.yaml").exists() {
-            self.add_to_archive(&mut tar, "pnpm-lock.yaml")?;
+    /// Run the pre-build commands, the build command from aether.json or
+    /// else the runtime's own `build_script`, then the post-build commands.
+    pub(crate) async fn run_build_steps(
+        &self,
+        work_dir: &Path,
+        build_script: Option<(&str, Command)>,
+    ) -> Result<()> {
+        if self.skip_build {
+            self.output("⏭️  Skipping build, packaging prebuilt files");
+            return Ok(());
         }
 
-        // Add node_modules
-        if self.project_path.join("node_modules").exists() {
-            self.add_directory_to_archive(&mut tar, "node_modules")?;
-        }
+        let build = &self.project_config.build;
 
-        // Add source files (common patterns)
-        for pattern in &["src", "lib", "dist", "build", "public", "views"] {
-            let dir_path = self.project_path.join(pattern);
-            if dir_path.exists() && dir_path.is_dir() {
-                self.add_directory_to_archive(&mut tar, pattern)?;
-            }
+        for command in &build.pre_build {
+            self.output(&format!("🔹 Pre-build: {}", command));
+            self.run_command(
+                shell_command(command, work_dir),
+                "Running pre-build command...",
+                "Pre-build command failed",
+            )
+            .await?;
         }
 
-        // Add common files
-        for file in &["index.js", "server.js", "app.js", "main.js", ".env.example"] {
-            let file_path = self.project_path.join(file);
-            if file_path.exists() && file_path.is_file() {
-                self.add_to_archive(&mut tar, file)?;
-            }
+        if let Some(command) = &build.command {
+            self.output(&format!("🏗️  Running build command: {}", command));
+            self.run_command(
+                shell_command(command, work_dir),
+                "Building application...",
+                "Build command failed",
+            )
+            .await?;
+            self.output("✅ Build command completed successfully");
+        } else if let Some((script, cmd)) = build_script {
+            self.output(&format!("🏗️  Running build script '{}'...", script));
+            self.run_command(cmd, "Building application...", "Build script failed")
+                .await?;
+            self.output("✅ Build script completed successfully");
         }
 
-        tar.finish()?;
-        pb.finish_and_clear();
+        for command in &build.post_build {
+            self.output(&format!("🔹 Post-build: {}", command));
+            self.run_command(
+                shell_command(command, work_dir),
+                "Running post-build command...",
+                "Post-build command failed",
+            )
+            .await?;
+        }
 
-        self.output("✅ Artifact created successfully");
         Ok(())
     }
 
-    fn add_to_archive(
+    /// Package `paths` from `work_dir` into a compressed tarball, followed
+    /// by an [`ArtifactManifest`] of its files. Entries are written in
+    /// sorted order with normalized timestamps, ownership and permissions,
+    /// and the gzip header carries no name or time, so the same files
+    /// always produce the same bytes.
+    async fn create_artifact(
         &self,
-        tar: &mut TarBuilder<GzEncoder<File>>,
-        relative_path: &str,
+        work_dir: &Path,
+        entries: Vec<PathBuf>,
+        output_path: &Path,
+        dependency_layer: Option<&str>,
     ) -> Result<()> {
-        let full_path = self.project_path.join(relative_path);
-        if full_path.exists() {
-            tar.append_path_with_name(&full_path, relative_path)?;
-        }
+        let level = self
+            .compression_level
+            .unwrap_or(self.compression.default_level());
+        self.output(&format!(
+            "📦 Creating {} ({} level {})...",
+            output_path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default(),
+            self.compression.name(),
+            level
+        ));
+
+        let pb = self.spinner("Packaging application...");
+
+        // File hashes are filled in while writing the archive
+        let manifest = self.manifest(Default::default(), dependency_layer)?;
+        let (compression, mtime) = (self.compression, self.source_date_epoch);
+        let (work_dir, output_path) = (work_dir.to_path_buf(), output_path.to_path_buf());
+        let packaged = run_blocking(move || {
+            let file = File::create(&output_path)?;
+            match compression {
+                CompressionFormat::Gzip => {
+                    let encoder = GzBuilder::new()
+                        .mtime(0)
+                        .operating_system(255)
+                        .write(file, Compression::new(level.clamp(0, 9) as u32));
+                    write_archive(encoder, &work_dir, &entries, mtime, manifest)?.finish()?;
+                }
+                CompressionFormat::Zstd => {
+                    let mut encoder = zstd::Encoder::new(file, level)?;
+                    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+                    encoder.multithread(threads as u32)?;
+                    write_archive(encoder, &work_dir, &entries, mtime, manifest)?.finish()?;
+                }
+            }
+            Ok(())
+        })
+        .await;
+        pb.finish_and_clear();
+        packaged?;
+
+        self.output("✅ Artifact created successfully");
         Ok(())
     }
 
-    fn add_directory_to_archive(
+    fn manifest(
         &self,
-        tar: &mut TarBuilder<GzEncoder<File>>,
-        dir_name: &str,
-    ) -> Result<()> {
-        let dir_path = self.project_path.join(dir_name);
-        if dir_path.exists() && dir_path.is_dir() {
-            tar.append_dir_all(dir_name, &dir_path)?;
-        }
-        Ok(())
+        files: std::collections::BTreeMap<String, String>,
+        dependency_layer: Option<&str>,
+    ) -> Result<ArtifactManifest> {
+        // The source date instead of the clock, so that rebuilding unchanged
+        // sources gives the same artifact
+        let built_at =
+            chrono::DateTime::from_timestamp(self.source_date_epoch as i64, 0).unwrap_or_default();
+
+        Ok(ArtifactManifest {
+            app: self.get_app_name().to_string(),
+            version: self.get_version(),
+            runtime: self.detect_runtime(),
+            cli_version: env!("CARGO_PKG_VERSION").to_string(),
+            built_at,
+            lockfile: LockfileInfo::find(self.runtime.source_root())?,
+            git: GitInfo::collect(&self.project_path),
+            dependency_layer: dependency_layer.map(str::to_string),
+            files,
+        })
     }
 
     // Public getters for private fields
     pub fn get_project_path(&self) -> &PathBuf {
         &self.project_path
     }
+}
+
+/// Write `entries` and `manifest`, with the hash of every file, as a tarball
+/// into `writer`, returning the writer so the caller can finish compressing.
+fn write_archive<W: Write>(
+    writer: W,
+    work_dir: &Path,
+    entries: &[PathBuf],
+    mtime: u64,
+    mut manifest: ArtifactManifest,
+) -> Result<W> {
+    let mut tar = TarBuilder::new(writer);
+    for entry in entries {
+        append_archive_entry(&mut tar, work_dir, entry, mtime)?;
+        let full_path = work_dir.join(entry);
+        if std::fs::symlink_metadata(&full_path)?.is_file() {
+            manifest.files.insert(
+                entry.to_string_lossy().replace('\\', "/"),
+                crate::utils::sha256_file(&full_path)?,
+            );
+        }
+    }
+
+    let manifest = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::file());
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    tar.append_data(&mut header, MANIFEST_FILE, manifest.as_slice())?;
+
+    Ok(tar.into_inner()?)
+}
+
+/// Run filesystem-heavy build work (copying sources, packaging) on the
+/// blocking pool, so the build timeout can still fire meanwhile.
+pub(crate) async fn run_blocking<T, F>(work: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AetherError::build(format!("Build task failed: {}", e)))?
+}
+
+/// Run a user-supplied command line through the platform shell.
+pub(crate) fn shell_command(command: &str, work_dir: &Path) -> Command {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.current_dir(work_dir);
+    cmd
+}
+
+fn default_build_log_path(app_name: &str) -> PathBuf {
+    let file_name = format!(
+        "{}-{}.log",
+        app_name.replace(['/', '@'], ""),
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    crate::config::Config::logs_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join(file_name)
+}
+
+/// Forward each line read from a child pipe to `tx` until the pipe closes.
+fn spawn_line_reader<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+    tx: mpsc::UnboundedSender<String>,
+) {
+    tokio::spawn(async move {
+        let mut segments = BufReader::new(reader).split(b'\n');
+        while let Ok(Some(line)) = segments.next_segment().await {
+            let line = String::from_utf8_lossy(&line)
+                .trim_end_matches('\r')
+                .to_string();
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// A running build tool whose process tree is killed if it is dropped
+/// before exiting, e.g. when the build times out.
+struct ChildGuard(Child);
+
+impl ChildGuard {
+    fn spawn(cmd: &mut Command) -> std::io::Result<Self> {
+        let child = cmd.spawn()?;
+        if let (Some(pid), Ok(mut active)) = (child.id(), ACTIVE_BUILD_PROCESSES.lock()) {
+            active.push(pid);
+        }
+        Ok(Self(child))
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        let Some(pid) = self.0.id() else { return };
+        if let Ok(None) = self.0.try_wait() {
+            kill_process_tree(pid);
+            let _ = self.0.start_kill();
+        }
+        if let Ok(mut active) = ACTIVE_BUILD_PROCESSES.lock() {
+            active.retain(|&active_pid| active_pid != pid);
+        }
+    }
+}
+
+/// Kill every running build tool and remove clean-build staging
+/// directories, since build tools run in their own process group and do not
+/// receive Ctrl+C. Returns whether a build was running.
+pub fn cancel_active_builds() -> bool {
+    let mut cancelled = false;
+    if let Ok(mut active) = ACTIVE_BUILD_PROCESSES.lock() {
+        for pid in active.drain(..) {
+            kill_process_tree(pid);
+            cancelled = true;
+        }
+    }
+    if let Ok(mut dirs) = ACTIVE_STAGING_DIRS.lock() {
+        for dir in dirs.drain(..) {
+            let _ = std::fs::remove_dir_all(dir);
+            cancelled = true;
+        }
+    }
+    cancelled
+}
+
+/// Handle Ctrl+C: cancel running builds and exit, or, when no build is
+/// running, end the process the way Ctrl+C would without a handler.
+pub fn handle_interrupt() -> ! {
+    if cancel_active_builds() {
+        crate::utils::print_error("Interrupted");
+        std::process::exit(130);
+    }
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+        libc::raise(libc::SIGINT);
+    }
+    std::process::exit(130)
+}
+
+#[cfg(unix)]
+fn kill_process_tree(pid: u32) {
+    // The child leads its own process group, so signal the whole group
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(windows)]
+fn kill_process_tree(pid: u32) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Every file and directory to archive for `paths`, sorted so artifacts
+/// are reproducible.
+fn archive_entries(work_dir: &Path, paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    for path in paths {
+        collect_archive_entries(work_dir, path, &mut entries)?;
+    }
+    entries.sort();
+    entries.dedup();
+    // A manifest left over from an earlier artifact is replaced
+    entries.retain(|entry| entry != Path::new(MANIFEST_FILE));
+    Ok(entries)
+}
+
+/// Add `relative_path` and, for directories, everything below it to
+/// `entries`. Symlinks are added as links, not followed.
+fn collect_archive_entries(
+    work_dir: &Path,
+    relative_path: &Path,
+    entries: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let full_path = work_dir.join(relative_path);
+    let Ok(metadata) = std::fs::symlink_metadata(&full_path) else {
+        return Ok(());
+    };
+    entries.push(relative_path.to_path_buf());
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(&full_path)? {
+            collect_archive_entries(work_dir, &relative_path.join(entry?.file_name()), entries)?;
+        }
+    }
+    Ok(())
+}
+
+/// Append one file, directory or symlink with its timestamp set to
+/// `mtime`, owned by root and with permissions reduced to 0644 or 0755.
+fn append_archive_entry<W: Write>(
+    tar: &mut TarBuilder<W>,
+    work_dir: &Path,
+    relative_path: &Path,
+    mtime: u64,
+) -> Result<()> {
+    let full_path = work_dir.join(relative_path);
+    let metadata = std::fs::symlink_metadata(&full_path)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_metadata_in_mode(&metadata, tar::HeaderMode::Deterministic);
+    header.set_mtime(mtime);
+
+    if metadata.is_symlink() {
+        header.set_size(0);
+        tar.append_link(&mut header, relative_path, std::fs::read_link(&full_path)?)?;
+    } else if metadata.is_dir() {
+        tar.append_data(&mut header, relative_path, std::io::empty())?;
+    } else {
+        tar.append_data(&mut header, relative_path, File::open(&full_path)?)?;
+    }
+    Ok(())
+}
+
+fn source_date_epoch_from_env() -> Option<u64> {
+    std::env::var("SOURCE_DATE_EPOCH").ok()?.trim().parse().ok()
+}
+
+/// Commit time of `HEAD` in the git repository containing `project_path`.
+fn last_commit_time(project_path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("git")
+        .args(["log", "-1", "--format=%ct"])
+        .current_dir(project_path)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Recursively copy `src` into `dst`, skipping the entries of `src` named in
+/// `excludes`. Excludes only apply at the top level, so e.g. `target` skips
+/// `src/../target` but keeps `src/target`. Symlinks are copied as links.
+pub(crate) fn copy_dir_excluding(src: &Path, dst: &Path, excludes: &[&str]) -> Result<()> {
+    std::fs::create_dir_all(dst)?;
+    copy_dir_contents(src, dst, excludes)
+}
+
+/// Copy the contents of `src` into the existing directory `dst`. Stops with
+/// an error if `dst` disappears, e.g. when a timed-out build's staging
+/// directory is removed.
+fn copy_dir_contents(src: &Path, dst: &Path, excludes: &[&str]) -> Result<()> {
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if excludes.iter().any(|excluded| name == *excluded) {
+            continue;
+        }
+
+        let target = dst.join(&name);
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            std::fs::create_dir(&target)?;
+            copy_dir_contents(&entry.path(), &target, &[])?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(link: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(link)?, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(link: &Path, target: &Path) -> Result<()> {
+    // Creating symlinks may need extra privileges here; copy what the link
+    // points to, skipping directories to avoid following cycles
+    if link.is_file() {
+        std::fs::copy(link, target)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_site(dir: &Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    async fn build_site(dir: &Path, output: PathBuf, compression: CompressionFormat) -> String {
+        let artifact = ProjectBuilder::new(dir)
+            .unwrap()
+            .with_build_log(output.with_extension("log"))
+            .with_reproducible(true)
+            .with_compression(compression, None)
+            .with_output_callback(|_| {})
+            .build(Some(output))
+            .await
+            .unwrap();
+        crate::utils::sha256_file(&artifact).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_build_output_is_streamed_and_logged() {
+        let dir = tempfile::tempdir().unwrap();
+        write_site(dir.path(), &[("index.html", "<h1>Hello</h1>")]);
+        let log = dir.path().join("build.log");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let builder = ProjectBuilder::new(dir.path())
+            .unwrap()
+            .with_build_log(&log)
+            .with_output_callback(move |line| {
+                let _ = tx.send(line.to_string());
+            });
+
+        let run = builder.run_command(
+            shell_command("echo first && sleep 1 && echo second >&2", dir.path()),
+            "Building...",
+            "Build failed",
+        );
+        tokio::pin!(run);
+        // The first line arrives while the command is still running
+        let first = tokio::select! {
+            line = rx.recv() => line,
+            _ = &mut run => panic!("output was only delivered once the command ended"),
+        };
+        assert_eq!(first.as_deref(), Some("first"));
+        run.await.unwrap();
+        assert_eq!(rx.recv().await.as_deref(), Some("second"));
+
+        let log = std::fs::read_to_string(&log).unwrap();
+        assert!(log.contains("$ sh -c echo first && sleep 1 && echo second >&2\nfirst\nsecond\n"));
+
+        let failing = builder.run_command(
+            shell_command("echo oops && exit 3", dir.path()),
+            "Building...",
+            "Build failed",
+        );
+        let error = failing.await.unwrap_err().to_string();
+        assert!(error.contains("oops"));
+        assert!(error.contains(&format!(
+            "Full build log: {}",
+            builder.build_log_path().display()
+        )));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_slow_build_is_killed_at_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        write_site(
+            dir.path(),
+            &[
+                ("index.html", "<h1>Hello</h1>"),
+                (
+                    "aether.json",
+                    r#"{ "build": { "command": "sleep 1 && touch finished" } }"#,
+                ),
+            ],
+        );
+
+        let started = std::time::Instant::now();
+        let result = ProjectBuilder::new(dir.path())
+            .unwrap()
+            .with_build_log(out.path().join("build.log"))
+            .with_build_timeout(Duration::from_millis(200))
+            .with_output_callback(|_| {})
+            .build(Some(out.path().join("site.tar.gz")))
+            .await;
+        assert!(matches!(
+            result,
+            Err(AetherError::BuildTimeout {
+                timeout_secs: 0,
+                ..
+            })
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // The build command was killed rather than left running
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(!dir.path().join("finished").exists());
+    }
 
-    pub fn get_package_json(&self) -> &PackageJson {
-        &self.package_json
+    #[tokio::test]
+    async fn test_clean_build_skips_only_top_level_excludes() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site");
+        write_site(
+            &site,
+            &[
+                ("index.html", "<h1>Hello</h1>"),
+                ("src/target/keep.js", "kept"),
+                ("target/debug/drop.js", "dropped"),
+            ],
+        );
+
+        let artifact = ProjectBuilder::new(&site)
+            .unwrap()
+            .with_clean_build(true)
+            .with_output_callback(|_| {})
+            .build(Some(out.path().join("site.tar.gz")))
+            .await
+            .unwrap();
+        let unpacked = out.path().join("unpacked");
+        crate::manifest::unpack_artifact(&artifact, &unpacked).unwrap();
+
+        assert!(unpacked.join("src/target/keep.js").is_file());
+        assert!(!unpacked.join("target").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dir_excluding_copies_symlinks_as_links() {
+        let src = tempfile::tempdir().unwrap();
+        let dst = tempfile::tempdir().unwrap();
+        write_site(src.path(), &[("lib/index.js", "module.exports = 1")]);
+        // Would recurse forever if followed
+        std::os::unix::fs::symlink("..", src.path().join("lib/parent")).unwrap();
+        std::os::unix::fs::symlink("lib/index.js", src.path().join("main.js")).unwrap();
+
+        let copy = dst.path().join("copy");
+        copy_dir_excluding(src.path(), &copy, STAGING_EXCLUDES).unwrap();
+
+        assert_eq!(
+            std::fs::read_link(copy.join("lib/parent")).unwrap(),
+            Path::new("..")
+        );
+        assert_eq!(
+            std::fs::read_to_string(copy.join("main.js")).unwrap(),
+            "module.exports = 1"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_archive_keeps_symlinks_as_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        write_site(
+            dir.path(),
+            &[
+                ("index.html", "<h1>Hello</h1>"),
+                ("lib/.store/lodash/index.js", "module.exports = {}"),
+            ],
+        );
+        // A cycle, and a pnpm-style link that must not be duplicated
+        std::os::unix::fs::symlink("..", dir.path().join("lib/parent")).unwrap();
+        std::os::unix::fs::symlink(".store/lodash", dir.path().join("lib/lodash")).unwrap();
+
+        let artifact = out.path().join("site.tar.gz");
+        build_site(dir.path(), artifact.clone(), CompressionFormat::Gzip).await;
+
+        let mut archive =
+            tar::Archive::new(flate2::read::GzDecoder::new(File::open(&artifact).unwrap()));
+        let mut links = Vec::new();
+        let mut files = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().into_owned();
+            if entry.header().entry_type().is_symlink() {
+                let target = entry.link_name().unwrap().unwrap();
+                links.push((path, target.to_string_lossy().into_owned()));
+            } else if entry.header().entry_type().is_file() {
+                files.push(path);
+            }
+        }
+        links.sort();
+        assert_eq!(
+            links,
+            [
+                ("lib/lodash".to_string(), ".store/lodash".to_string()),
+                ("lib/parent".to_string(), "..".to_string()),
+            ]
+        );
+        assert_eq!(
+            files
+                .iter()
+                .filter(|file| file.ends_with("index.js"))
+                .count(),
+            1
+        );
+
+        let verification = crate::manifest::verify_artifact(&artifact).unwrap();
+        assert!(verification.modified.is_empty() && verification.missing.is_empty());
+        let unpacked = out.path().join("unpacked");
+        crate::manifest::unpack_artifact(&artifact, &unpacked).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(unpacked.join("lib/lodash/index.js")).unwrap(),
+            "module.exports = {}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zstd_artifact_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let data: String = (0..20_000u64)
+            .map(|i| format!("row {} {}\n", i, i * i % 9973))
+            .collect();
+        write_site(
+            dir.path(),
+            &[("index.html", "<h1>Hello</h1>"), ("data.txt", &data)],
+        );
+
+        let mut artifacts = Vec::new();
+        for level in [1, 19] {
+            let artifact = ProjectBuilder::new(dir.path())
+                .unwrap()
+                .with_build_log(out.path().join("build.log"))
+                .with_compression(CompressionFormat::Zstd, Some(level))
+                .with_output_callback(|_| {})
+                .build(Some(out.path().join(format!("level-{}.tar.zst", level))))
+                .await
+                .unwrap();
+            artifacts.push(artifact);
+        }
+        let (fast, small) = (&artifacts[0], &artifacts[1]);
+
+        let magic = std::fs::read(small).unwrap()[..4].to_vec();
+        assert_eq!(magic, [0x28, 0xb5, 0x2f, 0xfd]);
+        assert!(std::fs::metadata(small).unwrap().len() < std::fs::metadata(fast).unwrap().len());
+
+        assert!(crate::manifest::verify_artifact(small).unwrap().is_valid());
+        let unpacked = out.path().join("unpacked");
+        crate::manifest::unpack_artifact(small, &unpacked).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(unpacked.join("data.txt")).unwrap(),
+            data
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_builds_reuse_one_artifact() {
+        use crate::artifact_store::ArtifactStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        write_site(dir.path(), &[("index.html", "<h1>Hello</h1>")]);
+        let store = crate::artifact_store::LocalStore::new(out.path().join("store"));
+        let format = crate::artifact_store::ArtifactFormat {
+            artifact_type: ArtifactType::Tarball,
+            compression: CompressionFormat::Gzip,
+        };
+
+        let mut uploads = Vec::new();
+        for name in ["first", "second"] {
+            let artifact = ProjectBuilder::new(dir.path())
+                .unwrap()
+                .with_build_log(out.path().join("build.log"))
+                .with_output_callback(|_| {})
+                .build(Some(out.path().join(format!("{}.tar.gz", name))))
+                .await
+                .unwrap();
+            uploads.push(
+                store
+                    .upload_artifact(&artifact, uuid::Uuid::nil(), "1.0.0", format)
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert!(!uploads[0].reused);
+        assert!(uploads[1].reused);
+        assert_eq!(uploads[1].sha256, uploads[0].sha256);
+    }
+
+    #[tokio::test]
+    async fn test_artifact_is_reproducible() {
+        let files = [
+            ("index.html", "<h1>Hello</h1>"),
+            ("assets/app.js", "console.log('hi')"),
+            ("assets/style.css", "h1 { color: red }"),
+            ("about/index.html", "<h1>About</h1>"),
+        ];
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        // The app name comes from the directory name
+        let (first_site, second_site) = (first.path().join("site"), second.path().join("site"));
+
+        write_site(&first_site, &files);
+        // Same files written in another order, with other timestamps
+        let mut reversed = files;
+        reversed.reverse();
+        write_site(&second_site, &reversed);
+        let later = std::time::SystemTime::now() + Duration::from_secs(3600);
+        for (path, _) in &files {
+            File::options()
+                .write(true)
+                .open(second_site.join(path))
+                .unwrap()
+                .set_modified(later)
+                .unwrap();
+        }
+
+        for compression in [CompressionFormat::Gzip, CompressionFormat::Zstd] {
+            let artifact = |name: &str| {
+                out.path()
+                    .join(format!("{}.{}", name, compression.extension()))
+            };
+            let first_hash = build_site(&first_site, artifact("first"), compression).await;
+            let second_hash = build_site(&second_site, artifact("second"), compression).await;
+            assert_eq!(first_hash, second_hash);
+        }
     }
 }
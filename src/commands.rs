@@ -1,15 +1,24 @@
 use crate::{
-    api::{ApiClient, Application, CreateAppRequest},
-    builder::ProjectBuilder,
+    api::{
+        ApiClient, Application, ArtifactType, CompressionFormat, CreateAppRequest,
+        DependencyLayerRef, DeployRequest, Deployment, RolloutState, RolloutStrategy,
+    },
+    artifact_store::{self, ArtifactFormat},
+    builder::{DependencyLayer, ProjectBuilder},
     config::Config,
-    presigned_uploader::PresignedUploader,
-    terminal_dashboard, utils, Result,
+    health::HealthCheck,
+    manifest,
+    platform::Platform,
+    preview, rollout, terminal_dashboard, upload, utils, Result,
 };
 use chrono;
 use clap::{Parser, Subcommand};
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::{io::Write, path::PathBuf};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 use uuid::Uuid;
 
 /// Safe password input with fallback for non-TTY environments
@@ -101,6 +110,63 @@ pub enum Commands {
         /// Force redeploy even if app exists
         #[arg(short, long)]
         force: bool,
+        /// Deploy to a preview application named after the current git branch
+        #[arg(long)]
+        preview: bool,
+        /// Build in an isolated staging directory with a frozen-lockfile install
+        #[arg(long)]
+        clean: bool,
+        /// Deploy a single package of an npm/yarn/pnpm workspace
+        #[arg(short, long)]
+        workspace: Option<String>,
+        /// Skip the build step and package prebuilt files as-is
+        #[arg(long)]
+        no_build: bool,
+        /// Print all build tool output
+        #[arg(short, long)]
+        verbose: bool,
+        /// Upload the build log alongside the deployment artifact
+        #[arg(long)]
+        upload_build_log: bool,
+        /// Build and deploy an OCI image from a Dockerfile (default: ./Dockerfile)
+        #[arg(long, num_args = 0..=1, default_missing_value = "Dockerfile", conflicts_with_all = ["workspace", "no_build"])]
+        dockerfile: Option<PathBuf>,
+        /// Produce a byte-identical artifact for the same sources and lockfile (implies --clean)
+        #[arg(long, conflicts_with = "dockerfile")]
+        reproducible: bool,
+        /// Artifact compression format
+        #[arg(long, value_enum, default_value = "gzip")]
+        compression: CompressionFormat,
+        /// Compression level (gzip: 0-9, default 6; zstd: 1-22, default 3)
+        #[arg(long)]
+        compression_level: Option<i32>,
+        /// Upload dependencies inside the artifact instead of as a cached layer
+        #[arg(long)]
+        no_dependency_layer: bool,
+        /// Install native dependencies for this platform, e.g. linux-x64 or linux-arm64
+        #[arg(long, conflicts_with = "dockerfile")]
+        target_platform: Option<Platform>,
+        /// How traffic moves to the new deployment
+        #[arg(long, value_enum, default_value = "all-at-once")]
+        strategy: RolloutStrategy,
+        /// Traffic percentages of the canary steps (default: 10,50,100)
+        #[arg(long, value_delimiter = ',')]
+        steps: Vec<u8>,
+        /// Time to wait before each promotion, e.g. 2m (default: 5m for canary, none for blue-green)
+        #[arg(long, value_parser = utils::parse_duration)]
+        interval: Option<std::time::Duration>,
+        /// Leave the rollout to `aether promote` and `aether abort`
+        #[arg(long)]
+        no_promote: bool,
+        /// Health path the new deployment must answer, or be rolled back, e.g. /healthz
+        #[arg(long = "health-check", value_name = "PATH")]
+        health_path: Option<String>,
+        /// Time the deployment has to become healthy, e.g. 2m (default: 2m)
+        #[arg(long, value_parser = utils::parse_duration)]
+        health_timeout: Option<std::time::Duration>,
+        /// Skip the health checks configured in aether.json
+        #[arg(long)]
+        no_health_check: bool,
     },
     /// List deployed applications
     List,
@@ -130,7 +196,8 @@ pub enum Commands {
     },
     /// Interactive dashboard mode
     Dashboard,
-    /// S3 operations
+    /// S3 operations (deprecated, use `aether artifact`)
+    #[command(hide = true)]
     S3 {
         #[command(subcommand)]
         action: S3Commands,
@@ -140,6 +207,114 @@ pub enum Commands {
         #[command(subcommand)]
         action: DomainCommands,
     },
+    /// Deployment artifact tools
+    Artifact {
+        #[command(subcommand)]
+        action: ArtifactCommands,
+    },
+    /// Move an in-progress canary or blue-green rollout to its next step
+    Promote {
+        /// Application name or UUID
+        app: String,
+        /// Promote straight to all traffic
+        #[arg(long)]
+        all: bool,
+    },
+    /// Abort an in-progress rollout, sending all traffic back to the previous deployment
+    Abort {
+        /// Application name or UUID
+        app: String,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Preview deployments of git branches
+    Preview {
+        #[command(subcommand)]
+        action: PreviewCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PreviewCommands {
+    /// List the previews of an application
+    List {
+        /// Application name or UUID
+        app: String,
+    },
+    /// Delete the preview of a branch
+    Delete {
+        /// Application name or UUID
+        app: String,
+        /// Branch of the preview (default: the current branch)
+        #[arg(short, long)]
+        branch: Option<String>,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArtifactCommands {
+    /// Upload an artifact for an application without deploying it
+    Upload {
+        /// Application name or UUID
+        app: String,
+        /// Artifact tarball (.tar.gz or .tar.zst)
+        file: PathBuf,
+        /// Version to record for the artifact
+        version: String,
+    },
+    /// List the artifacts stored for an application
+    List {
+        /// Application name or UUID
+        app: String,
+    },
+    /// Check an artifact's files against its embedded manifest
+    Verify {
+        /// Artifact tarball (.tar.gz or .tar.zst)
+        file: PathBuf,
+    },
+    /// Download the artifact of a past deployment
+    Download {
+        /// Application name or UUID
+        app: String,
+        /// Deployment to download the artifact of (default: the latest)
+        #[arg(long)]
+        deployment: Option<Uuid>,
+        /// Where to save the artifact, or with --unpack the directory to
+        /// extract it into
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Extract the artifact instead of keeping the tarball
+        #[arg(long)]
+        unpack: bool,
+    },
+    /// Delete a stored artifact
+    Delete {
+        /// Application name or UUID
+        app: String,
+        /// SHA-256 of the artifact, or a unique prefix of it
+        sha256: String,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Delete all but the newest stored artifacts
+    Prune {
+        /// Number of artifacts to keep, besides those deployed
+        #[arg(long)]
+        keep: usize,
+        /// Application name or UUID
+        app: String,
+        /// Only show what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -181,6 +356,32 @@ pub enum DomainCommands {
     },
 }
 
+/// Build-related options of `aether deploy`.
+struct BuildFlags {
+    clean: bool,
+    workspace: Option<String>,
+    no_build: bool,
+    verbose: bool,
+    upload_build_log: bool,
+    dockerfile: Option<PathBuf>,
+    reproducible: bool,
+    compression: CompressionFormat,
+    compression_level: Option<i32>,
+    no_dependency_layer: bool,
+    target_platform: Option<Platform>,
+}
+
+/// How `aether deploy` moves traffic to the new deployment and checks it.
+struct RolloutFlags {
+    strategy: RolloutStrategy,
+    steps: Vec<u8>,
+    interval: Option<std::time::Duration>,
+    no_promote: bool,
+    health_path: Option<String>,
+    health_timeout: Option<std::time::Duration>,
+    no_health_check: bool,
+}
+
 pub async fn execute_command(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Register {
@@ -199,7 +400,57 @@ pub async fn execute_command(cli: Cli) -> Result<()> {
             runtime,
             path,
             force,
-        } => deploy_command(name, runtime, path, force).await,
+            preview,
+            clean,
+            workspace,
+            no_build,
+            verbose,
+            upload_build_log,
+            dockerfile,
+            reproducible,
+            compression,
+            compression_level,
+            no_dependency_layer,
+            target_platform,
+            strategy,
+            steps,
+            interval,
+            no_promote,
+            health_path,
+            health_timeout,
+            no_health_check,
+        } => {
+            deploy_command(
+                name,
+                runtime,
+                path,
+                force,
+                preview,
+                BuildFlags {
+                    clean,
+                    workspace,
+                    no_build,
+                    verbose,
+                    upload_build_log,
+                    dockerfile,
+                    reproducible,
+                    compression,
+                    compression_level,
+                    no_dependency_layer,
+                    target_platform,
+                },
+                RolloutFlags {
+                    strategy,
+                    steps,
+                    interval,
+                    no_promote,
+                    health_path,
+                    health_timeout,
+                    no_health_check,
+                },
+            )
+            .await
+        }
         Commands::List => list_command().await,
         Commands::Logs { app, lines, follow } => logs_command(app, lines, follow).await,
         Commands::Delete { app, yes } => delete_command(app, yes).await,
@@ -207,6 +458,10 @@ pub async fn execute_command(cli: Cli) -> Result<()> {
         Commands::Dashboard => dashboard_command().await,
         Commands::S3 { action } => s3_command(action).await,
         Commands::Domain { action } => domain_command(action).await,
+        Commands::Artifact { action } => artifact_command(action).await,
+        Commands::Promote { app, all } => promote_command(app, all).await,
+        Commands::Abort { app, yes } => abort_command(app, yes).await,
+        Commands::Preview { action } => preview_command(action).await,
     }
 }
 
@@ -346,6 +601,9 @@ async fn deploy_command(
     runtime: Option<String>,
     path: Option<PathBuf>,
     force: bool,
+    preview: bool,
+    flags: BuildFlags,
+    rollout: RolloutFlags,
 ) -> Result<()> {
     let config = Config::load()?;
 
@@ -359,16 +617,96 @@ async fn deploy_command(
         return Ok(());
     }
 
-    let project_path = path.unwrap_or_else(|| std::env::current_dir().unwrap());
+    if let Some(level) = flags.compression_level {
+        let levels = flags.compression.levels();
+        if !levels.contains(&level) {
+            return Err(crate::AetherError::config(format!(
+                "Invalid {} compression level {} (expected {}-{})",
+                flags.compression.name(),
+                level,
+                levels.start(),
+                levels.end()
+            )));
+        }
+    }
+
+    let canary_steps = match rollout.strategy {
+        RolloutStrategy::Canary if rollout.steps.is_empty() => {
+            rollout::DEFAULT_CANARY_STEPS.to_vec()
+        }
+        RolloutStrategy::Canary => {
+            rollout::validate_canary_steps(&rollout.steps)?;
+            rollout.steps.clone()
+        }
+        _ if !rollout.steps.is_empty() => {
+            return Err(crate::AetherError::config(
+                "--steps needs --strategy canary",
+            ));
+        }
+        RolloutStrategy::AllAtOnce if rollout.interval.is_some() || rollout.no_promote => {
+            return Err(crate::AetherError::config(
+                "--interval and --no-promote need --strategy canary or blue-green",
+            ));
+        }
+        _ => Vec::new(),
+    };
+
+    if rollout.no_health_check
+        && (rollout.health_path.is_some() || rollout.health_timeout.is_some())
+    {
+        return Err(crate::AetherError::config(
+            "--no-health-check conflicts with --health-check and --health-timeout",
+        ));
+    }
 
-    // Find project root if we're in a subdirectory
-    let project_root = utils::find_project_root(&project_path).unwrap_or(project_path);
+    let project_path = path.unwrap_or_else(|| std::env::current_dir().unwrap());
 
     println!("🚀 {}", style("Starting deployment...").bold());
-    println!("📁 Project path: {}", project_root.display());
 
     // Initialize project builder
-    let builder = ProjectBuilder::new(&project_root)?;
+    let builder = if let Some(package) = &flags.workspace {
+        ProjectBuilder::for_workspace(&project_path, package)?
+    } else if let Some(dockerfile) = &flags.dockerfile {
+        ProjectBuilder::for_dockerfile(&project_path, dockerfile)?
+    } else {
+        // Find project root if we're in a subdirectory
+        let project_root = utils::find_project_root(&project_path).unwrap_or(project_path);
+        ProjectBuilder::new(&project_root)?
+    };
+    println!("📁 Project path: {}", builder.get_project_path().display());
+
+    let builder = builder
+        .with_clean_build(flags.clean)
+        .with_reproducible(flags.reproducible)
+        .with_compression(flags.compression, flags.compression_level)
+        .with_target_platform(flags.target_platform)
+        .with_skip_build(flags.no_build)
+        .with_verbose(flags.verbose)
+        .with_build_timeout(std::time::Duration::from_secs(config.build_timeout));
+
+    // Health checks from aether.json, overridden by the command line
+    let health_check = if rollout.no_health_check {
+        None
+    } else {
+        let configured = builder
+            .health_check()
+            .map(HealthCheck::from_config)
+            .transpose()?;
+        let check = match (configured, rollout.health_path.clone()) {
+            (Some(check), Some(path)) => Some(check.with_path(path)),
+            (None, Some(path)) => Some(HealthCheck::new(path)),
+            (check, None) => check,
+        };
+        match (check, rollout.health_timeout) {
+            (Some(check), Some(timeout)) => Some(check.with_timeout(timeout)),
+            (None, Some(_)) => {
+                return Err(crate::AetherError::config(
+                    "--health-timeout needs --health-check or a health_check in aether.json",
+                ));
+            }
+            (check, None) => check,
+        }
+    };
 
     // Determine app name
     let app_name = if let Some(name) = name {
@@ -383,17 +721,42 @@ async fn deploy_command(
     // Determine runtime
     let app_runtime = runtime.unwrap_or_else(|| builder.detect_runtime());
 
+    let preview_branch = if preview {
+        let branch = preview::current_branch(builder.get_project_path()).ok_or_else(|| {
+            crate::AetherError::Other(
+                "--preview needs a git repository with a branch checked out".to_string(),
+            )
+        })?;
+        Some(branch)
+    } else {
+        None
+    };
+
     println!("📦 App name: {}", style(&app_name).cyan());
+    if let Some(branch) = &preview_branch {
+        println!("🌿 Preview of branch: {}", style(branch).cyan());
+    }
     println!("🏷️  Version: {}", style(builder.get_version()).cyan());
     println!("🔧 Runtime: {}", style(&app_runtime).cyan());
+    if let Some(start_command) = builder.start_command() {
+        println!("🏁 Start command: {}", style(start_command).cyan());
+    }
 
     // Create API client
-    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
 
     // Check if app already exists
     let existing_app = find_app_by_name(&client, &app_name).await?;
 
-    let app = if let Some(existing_app) = existing_app {
+    let app = if let Some(branch) = &preview_branch {
+        let parent = existing_app.ok_or_else(|| {
+            crate::AetherError::Other(format!(
+                "Application '{}' not found, deploy it before its previews",
+                app_name
+            ))
+        })?;
+        find_or_create_preview(&client, &parent, branch, &app_runtime, &builder).await?
+    } else if let Some(existing_app) = existing_app {
         if !force {
             let should_continue = utils::confirm(&format!(
                 "Application '{}' already exists. Continue with deployment?",
@@ -411,23 +774,123 @@ async fn deploy_command(
         println!("📝 Creating new application...");
         let create_request = CreateAppRequest {
             name: app_name.clone(),
-            description: Some(format!("NodeJS application deployed via AetherEngine CLI")),
+            description: Some(format!(
+                "{} application deployed via AetherEngine CLI",
+                builder.runtime().display_name()
+            )),
             runtime: app_runtime.clone(),
+            start_command: builder.start_command(),
+            parent_app_id: None,
+            preview_branch: None,
         };
 
         client.create_application(create_request).await?
     };
 
+    // Files are uploaded to the configured store, streaming each from disk;
+    // the progress bar stays hidden until the first upload
+    let (pb, progress) = upload::progress_bar("Artifact");
+    let bar = pb.clone();
+    let store = artifact_store::open(
+        &config,
+        client.clone(),
+        Some(progress),
+        Some(Box::new(move |line| bar.suspend(|| println!("{}", line)))),
+    )
+    .await?;
+
+    // Reuse the dependency layer of an earlier deployment if the lockfile
+    // has not changed since
+    let cached_layer = match builder.dependency_layer_key() {
+        Some(key) if !flags.no_dependency_layer => {
+            store
+                .find_dependency_layer(app.id, &key, builder.compression())
+                .await?
+        }
+        _ => None,
+    };
+    let builder = builder.with_cached_dependency_layer(cached_layer.is_some());
+
     // Build the application
-    let artifact_path = builder.build(None).await?;
+    let (artifact_path, dependency_layer) = if flags.no_dependency_layer {
+        (builder.build(None).await?, None)
+    } else {
+        let artifacts = builder.build_layers(None).await?;
+        (artifacts.app, artifacts.dependency_layer)
+    };
 
     // Get artifact size for display
-    let artifact_size = {
-        let metadata = std::fs::metadata(&artifact_path)?;
-        utils::format_size(metadata.len())
+    let artifact_size = std::fs::metadata(&artifact_path)?.len();
+
+    pb.suspend(|| {
+        println!(
+            "📤 Uploading artifact to {} ({})...",
+            store.describe(),
+            utils::format_size(artifact_size)
+        )
+    });
+    let artifact = store
+        .upload_artifact(
+            &artifact_path,
+            app.id,
+            &builder.get_version(),
+            ArtifactFormat {
+                artifact_type: builder.artifact_type(),
+                compression: builder.compression(),
+            },
+        )
+        .await?;
+    // Best effort: the artifact is stored whether or not a download URL
+    // can be had for it
+    let download = store
+        .download_url(app.id, &artifact.key)
+        .await
+        .unwrap_or(None);
+    if artifact.reused {
+        pb.suspend(|| println!("{}", upload::reused_message(artifact_size)));
+    }
+
+    let dependency_layer = match dependency_layer {
+        Some(DependencyLayer {
+            key,
+            path: Some(path),
+        }) => {
+            pb.reset();
+            pb.set_message("Dependency layer");
+            let layer = store
+                .upload_dependency_layer(
+                    &path,
+                    app.id,
+                    &builder.get_version(),
+                    &key,
+                    builder.compression(),
+                )
+                .await?;
+            std::fs::remove_file(&path)?;
+            Some(DependencyLayerRef {
+                key,
+                artifact_url: layer.key,
+                sha256: Some(layer.sha256),
+            })
+        }
+        Some(DependencyLayer { path: None, .. }) => cached_layer,
+        None => None,
+    };
+
+    let build_log_url = if flags.upload_build_log {
+        pb.reset();
+        pb.set_message("Build log");
+        Some(
+            store
+                .upload_build_log(builder.build_log_path(), app.id, &builder.get_version())
+                .await?
+                .key,
+        )
+    } else {
+        None
     };
 
-    println!("📤 Uploading artifact to S3 ({})...", artifact_size);
+    pb.finish_and_clear();
 
     let pb = ProgressBar::new_spinner();
     pb.set_style(
@@ -435,20 +898,25 @@ async fn deploy_command(
             .template("{spinner:.green} {msg}")
             .unwrap(),
     );
-    pb.set_message("Uploading to S3...");
-    pb.enable_steady_tick(std::time::Duration::from_millis(100));
-
-    // Upload to S3 using presigned URL
-    let presigned_uploader = PresignedUploader::new(client.clone());
-    let (artifact_url, presigned_url) = presigned_uploader
-        .upload_artifact(&artifact_path, app.id, &builder.get_version())
-        .await?;
-
     pb.set_message("✅ Upload successful! Deploying application...");
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
 
     // Deploy the application with S3 URL (backend will generate presigned URL)
     let deployment = client
-        .deploy_application(app.id, builder.get_version(), artifact_url.clone())
+        .deploy_application(DeployRequest {
+            app_id: app.id,
+            version: builder.get_version(),
+            artifact_url: artifact.key.clone(),
+            artifact_sha256: Some(artifact.sha256.clone()),
+            build_log_url,
+            artifact_type: builder.artifact_type(),
+            compression: builder.compression(),
+            dependency_layer,
+            start_command: builder.start_command(),
+            static_site: builder.runtime().static_site(),
+            strategy: rollout.strategy,
+            canary_steps,
+        })
         .await?;
 
     pb.finish_and_clear();
@@ -460,8 +928,59 @@ async fn deploy_command(
     println!("🆔 App ID: {}", style(app.id).dim());
     println!("🚀 Deployment ID: {}", style(deployment.id).dim());
     println!("📊 Status: {}", style(&deployment.status).green());
-    println!("📦 Artifact: {}", style(&artifact_url).dim());
-    println!("🔗 Download URL: {}", style(&presigned_url).blue());
+    println!("📦 Artifact: {}", style(&artifact.key).dim());
+    println!("🔐 SHA-256: {}", style(&artifact.sha256).dim());
+    if let Some(download) = &download {
+        println!(
+            "🔗 Download URL (valid for {}): {}",
+            utils::format_duration(download.expires_in),
+            style(&download.download_url).blue()
+        );
+    }
+
+    let deployment = match &deployment.rollout {
+        Some(state) if rollout.no_promote => {
+            println!("{}", rollout::describe(state));
+            utils::print_info(&format!(
+                "Promote with: aether promote {}, or roll back with: aether abort {}",
+                app.name, app.name
+            ));
+            deployment
+        }
+        Some(state) => {
+            println!("{}", rollout::describe(state));
+            let interval = rollout.interval.unwrap_or(match rollout.strategy {
+                RolloutStrategy::Canary => rollout::DEFAULT_CANARY_INTERVAL,
+                _ => std::time::Duration::ZERO,
+            });
+            let deployment = rollout::drive(&client, &app.name, deployment, interval).await?;
+            if deployment
+                .rollout
+                .as_ref()
+                .is_some_and(|state| state.state == RolloutState::Aborted)
+            {
+                return Err(crate::AetherError::Other(format!(
+                    "Rollout of {} {} was aborted",
+                    app.name,
+                    builder.get_version()
+                )));
+            }
+            deployment
+        }
+        None => deployment,
+    };
+
+    if let Some(check) = &health_check {
+        if deployment
+            .rollout
+            .as_ref()
+            .is_some_and(|state| state.state == RolloutState::InProgress)
+        {
+            utils::print_info("Skipping health checks while the rollout is in progress");
+        } else {
+            check_health_or_roll_back(&client, &app, &deployment, check).await?;
+        }
+    }
 
     // Web Dashboard promotion
     println!();
@@ -476,10 +995,177 @@ async fn deploy_command(
     println!("╚═══════════════════════════════════════════════════════════════════════════╝");
     println!();
 
+    if let Some(parent_id) = app.parent_app_id {
+        // The platform assigns the URL on the first deployment
+        if let Some(url) = client.get_application(app.id).await?.deployment_url {
+            println!("🔍 Preview URL: {}", style(url).blue());
+        }
+        if let Err(e) = delete_stale_previews(&client, parent_id, builder.get_project_path()).await
+        {
+            utils::print_warning(&format!("Could not clean up old previews: {}", e));
+        }
+    }
+
     // Show logs command hint
-    utils::print_info(&format!("View logs with: aether logs {}", app_name));
-    utils::print_info("Presigned URL valid for 24 hours");
+    utils::print_info(&format!("View logs with: aether logs {}", app.name));
+
+    Ok(())
+}
+
+/// Wait for `deployment` to pass `check`, rolling back to the previous
+/// deployment when it does not become healthy in time.
+async fn check_health_or_roll_back(
+    client: &ApiClient,
+    app: &Application,
+    deployment: &Deployment,
+    check: &HealthCheck,
+) -> Result<()> {
+    // The platform assigns the URL on the first deployment
+    let Some(deployment_url) = client.get_application(app.id).await?.deployment_url else {
+        utils::print_warning("The application has no deployment URL yet, skipping health checks");
+        return Ok(());
+    };
+    let url = check.url(&deployment_url);
+    println!("🩺 Checking health of {}...", style(&url).blue());
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    let report = check
+        .run(&deployment_url, |report| {
+            pb.set_message(format!(
+                "{}/{} successful checks in a row{}",
+                report.consecutive,
+                check.successes,
+                report
+                    .last_failure
+                    .as_ref()
+                    .map(|failure| format!(", last failure: {}", failure))
+                    .unwrap_or_default()
+            ))
+        })
+        .await;
+    pb.finish_and_clear();
+    let report = report?;
+
+    if report.healthy {
+        utils::print_success(&format!(
+            "Healthy after {} checks in {}",
+            report.checks,
+            utils::format_duration(report.elapsed.as_secs())
+        ));
+        return Ok(());
+    }
+
+    utils::print_error(&format!(
+        "{} {} did not pass {} health checks in a row within {}",
+        app.name,
+        deployment.version,
+        check.successes,
+        utils::format_duration(check.timeout.as_secs())
+    ));
+    println!(
+        "   Checks: {} run, {} failed",
+        report.checks, report.failures
+    );
+    if let Some(failure) = &report.last_failure {
+        println!("   Last failure: {}", failure);
+    }
+
+    let deployments = client.list_deployments(app.id).await?;
+    let Some(previous) = rollout::rollback_target(&deployments, deployment) else {
+        return Err(crate::AetherError::Other(format!(
+            "Deployment {} failed its health checks, and no earlier deployment ran successfully to roll back to",
+            deployment.id
+        )));
+    };
+
+    println!(
+        "⏪ Rolling back to {} (deployment {})...",
+        previous.version,
+        style(previous.id).dim()
+    );
+    let rolled_back = client.rollback_application(app.id, previous.id).await?;
+    utils::print_success(&format!(
+        "Rolled back, {} serves {} again",
+        app.name, previous.version
+    ));
+    println!("🚀 Deployment ID: {}", style(rolled_back.id).dim());
+
+    Err(crate::AetherError::Other(format!(
+        "Deployment {} of {} failed its health checks and was rolled back",
+        deployment.version, app.name
+    )))
+}
+
+/// The preview application of `parent` for `branch`, created on its first
+/// deployment.
+async fn find_or_create_preview(
+    client: &ApiClient,
+    parent: &Application,
+    branch: &str,
+    runtime: &str,
+    builder: &ProjectBuilder,
+) -> Result<Application> {
+    let name = preview::preview_name(&parent.name, branch);
+    match find_app_by_name(client, &name).await? {
+        Some(app) if app.parent_app_id == Some(parent.id) => Ok(app),
+        Some(_) => Err(crate::AetherError::Other(format!(
+            "Application '{}' already exists and is not a preview of '{}'",
+            name, parent.name
+        ))),
+        None => {
+            println!("📝 Creating preview application {}...", style(&name).cyan());
+            client
+                .create_application(CreateAppRequest {
+                    name,
+                    description: Some(format!("Preview of {} for branch {}", parent.name, branch)),
+                    runtime: runtime.to_string(),
+                    start_command: builder.start_command(),
+                    parent_app_id: Some(parent.id),
+                    preview_branch: Some(branch.to_string()),
+                })
+                .await
+        }
+    }
+}
+
+/// The preview applications of `parent_id`.
+async fn list_previews(client: &ApiClient, parent_id: Uuid) -> Result<Vec<Application>> {
+    Ok(client
+        .list_applications()
+        .await?
+        .into_iter()
+        .filter(|app| app.parent_app_id == Some(parent_id))
+        .collect())
+}
 
+/// Delete the previews of `parent_id` whose branch no longer exists in the
+/// repository containing `repo`, locally or on any of its remotes. Nothing is
+/// deleted when a remote cannot be reached.
+async fn delete_stale_previews(client: &ApiClient, parent_id: Uuid, repo: &Path) -> Result<()> {
+    let Some(branches) = preview::live_branches(repo) else {
+        utils::print_warning("Could not reach the git remotes, keeping old previews");
+        return Ok(());
+    };
+    for app in list_previews(client, parent_id).await? {
+        let Some(branch) = &app.preview_branch else {
+            continue;
+        };
+        if branches.contains(branch) {
+            continue;
+        }
+        client.delete_application(app.id).await?;
+        println!(
+            "🧹 Deleted preview {} (branch {} no longer exists)",
+            style(&app.name).cyan(),
+            branch
+        );
+    }
     Ok(())
 }
 
@@ -840,38 +1526,13 @@ async fn s3_command(action: S3Commands) -> Result<()> {
             file,
             app_name,
             version,
-        } => s3_upload_command(file, app_name, version).await,
-    }
-}
-
-async fn s3_upload_command(file: PathBuf, _app_name: String, version: String) -> Result<()> {
-    if !file.exists() {
-        return Err(crate::AetherError::invalid_project(format!(
-            "File not found: {:?}",
-            file
-        )));
+        } => {
+            utils::print_warning(
+                "`aether s3 upload` is deprecated, use `aether artifact upload <app> <file> <version>`",
+            );
+            artifact_upload_command(app_name, file, version).await
+        }
     }
-
-    utils::print_info(&format!("Uploading {} to S3...", file.display()));
-
-    // Generate a UUID for the app (since this is a standalone upload)
-    let app_id = Uuid::new_v4();
-    utils::print_info(&format!("Generated app ID: {}", app_id));
-
-    // For standalone upload, we need to create API client
-    let config = Config::load()?;
-    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
-
-    let presigned_uploader = PresignedUploader::new(client);
-    let (artifact_url, presigned_url) = presigned_uploader
-        .upload_artifact(&file, app_id, &version)
-        .await?;
-
-    utils::print_success("✅ Upload successful!");
-    utils::print_info(&format!("Artifact URL: {}", artifact_url));
-    utils::print_info(&format!("Presigned URL: {}", presigned_url));
-
-    Ok(())
 }
 
 async fn domain_command(action: DomainCommands) -> Result<()> {
@@ -1046,3 +1707,725 @@ async fn domain_delete_command(app: String, domain: String, yes: bool) -> Result
 
     Ok(())
 }
+
+async fn artifact_command(action: ArtifactCommands) -> Result<()> {
+    match action {
+        ArtifactCommands::Upload { app, file, version } => {
+            artifact_upload_command(app, file, version).await
+        }
+        ArtifactCommands::List { app } => artifact_list_command(app).await,
+        ArtifactCommands::Verify { file } => artifact_verify_command(file),
+        ArtifactCommands::Download {
+            app,
+            deployment,
+            output,
+            unpack,
+        } => artifact_download_command(app, deployment, output, unpack).await,
+        ArtifactCommands::Delete { app, sha256, yes } => {
+            artifact_delete_command(app, sha256, yes).await
+        }
+        ArtifactCommands::Prune {
+            keep,
+            app,
+            dry_run,
+            yes,
+        } => artifact_prune_command(app, keep, dry_run, yes).await,
+    }
+}
+
+async fn artifact_upload_command(app: String, file: PathBuf, version: String) -> Result<()> {
+    if !file.exists() {
+        return Err(crate::AetherError::invalid_project(format!(
+            "File not found: {:?}",
+            file
+        )));
+    }
+
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to upload artifacts");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+
+    let (pb, progress) = upload::progress_bar("Artifact");
+    let bar = pb.clone();
+    let store = artifact_store::open(
+        &config,
+        client,
+        Some(progress),
+        Some(Box::new(move |line| bar.suspend(|| println!("{}", line)))),
+    )
+    .await?;
+    let size = std::fs::metadata(&file)?.len();
+    pb.suspend(|| {
+        println!(
+            "📤 Uploading {} {} to {} ({})...",
+            style(&app_details.name).cyan(),
+            version,
+            store.describe(),
+            utils::format_size(size)
+        )
+    });
+    let format = ArtifactFormat {
+        artifact_type: ArtifactType::Tarball,
+        compression: CompressionFormat::from_path(&file),
+    };
+    let uploaded = store.upload_artifact(&file, app_id, &version, format).await;
+    pb.finish_and_clear();
+    let artifact = uploaded?;
+    if artifact.reused {
+        println!("{}", upload::reused_message(size));
+    } else {
+        utils::print_success("Artifact uploaded");
+    }
+    println!("📦 Artifact: {}", style(&artifact.key).dim());
+    println!("🔐 SHA-256: {}", style(&artifact.sha256).dim());
+
+    Ok(())
+}
+
+async fn artifact_list_command(app: String) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to list artifacts");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+    let deployed = deployed_artifact_urls(&client, app_id).await?;
+
+    let store = artifact_store::open(&config, client, None, None).await?;
+    println!(
+        "📦 {}",
+        style(format!(
+            "Artifacts for '{}' in {}",
+            app_details.name,
+            store.describe()
+        ))
+        .bold()
+    );
+
+    let mut artifacts = store.list_artifacts(app_id).await?;
+    artifacts.sort_by_key(|artifact| std::cmp::Reverse(artifact.created_at));
+    if artifacts.is_empty() {
+        utils::print_info("No stored artifacts");
+        return Ok(());
+    }
+
+    println!(
+        "\n{:<14} {:<16} {:>10}  {:<18}",
+        "SHA-256", "VERSION", "SIZE", "CREATED"
+    );
+    println!("{}", "─".repeat(80));
+    for artifact in &artifacts {
+        let in_use = deployed
+            .iter()
+            .any(|url| artifact_store::is_same_artifact(artifact, url));
+        println!(
+            "{:<14} {:<16} {:>10}  {:<18} {}",
+            style(short_sha(&artifact.sha256)).cyan(),
+            artifact.version.as_deref().unwrap_or("-"),
+            utils::format_size(artifact.size),
+            artifact
+                .created_at
+                .map(|created| created.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            if in_use {
+                style("● deployed").green().to_string()
+            } else {
+                String::new()
+            }
+        );
+    }
+    println!(
+        "\n{} artifacts, {} in total",
+        artifacts.len(),
+        utils::format_size(artifacts.iter().map(|artifact| artifact.size).sum())
+    );
+
+    Ok(())
+}
+
+/// The first 12 hex digits of a SHA-256, enough to tell artifacts apart.
+fn short_sha(sha256: &str) -> &str {
+    sha256.get(..12).unwrap_or(sha256)
+}
+
+/// The artifact URLs of the application's deployments that are rolling out
+/// or serving traffic, which must never be deleted from under them.
+async fn deployed_artifact_urls(client: &ApiClient, app_id: Uuid) -> Result<Vec<String>> {
+    let deployments = client.list_deployments(app_id).await?;
+    Ok(rollout::live_deployments(&deployments)
+        .into_iter()
+        .filter_map(|deployment| deployment.artifact_url.clone())
+        .collect())
+}
+
+fn artifact_verify_command(file: PathBuf) -> Result<()> {
+    println!(
+        "🔍 {}",
+        style(format!("Verifying {}...", file.display())).bold()
+    );
+
+    let verification = manifest::verify_artifact(&file)?;
+    let manifest = &verification.manifest;
+    println!(
+        "📦 App: {} {}",
+        style(&manifest.app).cyan(),
+        style(&manifest.version).dim()
+    );
+    println!("⚙️  Runtime: {}", manifest.runtime);
+    println!(
+        "🕐 Built: {} (aether {})",
+        manifest.built_at.format("%Y-%m-%d %H:%M:%S UTC"),
+        manifest.cli_version
+    );
+    if let Some(git) = &manifest.git {
+        println!(
+            "🌿 Git: {}{}{}",
+            git.commit.get(..12).unwrap_or(&git.commit),
+            git.branch
+                .as_ref()
+                .map(|branch| format!(" on {}", branch))
+                .unwrap_or_default(),
+            if git.dirty { " (dirty)" } else { "" }
+        );
+    }
+    if let Some(lockfile) = &manifest.lockfile {
+        println!(
+            "🔒 Lockfile: {} {}",
+            lockfile.path,
+            style(lockfile.sha256.get(..12).unwrap_or(&lockfile.sha256)).dim()
+        );
+    }
+
+    if verification.is_valid() {
+        utils::print_success(&format!(
+            "All {} files match the manifest",
+            manifest.files.len()
+        ));
+        return Ok(());
+    }
+
+    for (label, files) in [
+        ("Modified", &verification.modified),
+        ("Missing", &verification.missing),
+        ("Not in manifest", &verification.unexpected),
+    ] {
+        for file in files {
+            println!("  {} {}", style(format!("{}:", label)).red(), file);
+        }
+    }
+    Err(crate::AetherError::Other(format!(
+        "Artifact does not match its manifest ({} modified, {} missing, {} unexpected)",
+        verification.modified.len(),
+        verification.missing.len(),
+        verification.unexpected.len()
+    )))
+}
+
+async fn artifact_download_command(
+    app: String,
+    deployment_id: Option<Uuid>,
+    output: Option<PathBuf>,
+    unpack: bool,
+) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to download artifacts");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+
+    let deployments = client.list_deployments(app_id).await?;
+    let deployment = match deployment_id {
+        Some(id) => deployments
+            .into_iter()
+            .find(|deployment| deployment.id == id)
+            .ok_or_else(|| {
+                crate::AetherError::Other(format!(
+                    "Deployment {} not found for app '{}'",
+                    id, app_details.name
+                ))
+            })?,
+        None => deployments
+            .into_iter()
+            .filter(|deployment| deployment.artifact_url.is_some())
+            .max_by_key(|deployment| deployment.created_at)
+            .ok_or_else(|| {
+                crate::AetherError::Other(format!(
+                    "App '{}' has no deployed artifacts",
+                    app_details.name
+                ))
+            })?,
+    };
+    let key = deployment.artifact_url.clone().ok_or_else(|| {
+        crate::AetherError::Other(format!(
+            "Deployment {} has no stored artifact",
+            deployment.id
+        ))
+    })?;
+
+    let filename = key.rsplit('/').next().unwrap_or(&key).to_string();
+    if unpack && !(filename.ends_with(".tar.gz") || filename.ends_with(".tar.zst")) {
+        return Err(crate::AetherError::Other(format!(
+            "Only tarball artifacts can be unpacked, {} is not one",
+            filename
+        )));
+    }
+    let unpack_dir = unpack.then(|| {
+        output.clone().unwrap_or_else(|| {
+            PathBuf::from(format!("{}-{}", app_details.name, deployment.version))
+        })
+    });
+    if let Some(dir) = &unpack_dir {
+        if std::fs::read_dir(dir).is_ok_and(|mut entries| entries.next().is_some()) {
+            return Err(crate::AetherError::Other(format!(
+                "{} already exists and is not empty",
+                dir.display()
+            )));
+        }
+    }
+    let tarball_path = match unpack_dir {
+        Some(_) => std::env::temp_dir().join(format!("aether-{}-{}", deployment.id, filename)),
+        None => output.unwrap_or_else(|| PathBuf::from(&filename)),
+    };
+
+    let store = artifact_store::open(&config, client, None, None).await?;
+    let download = store.download_url(app_id, &key).await?.ok_or_else(|| {
+        crate::AetherError::Other(format!(
+            "The artifact is stored at {}, but {} does not provide downloads",
+            key,
+            store.describe()
+        ))
+    })?;
+
+    println!(
+        "📥 Downloading {} {} ({})...",
+        style(&app_details.name).cyan(),
+        deployment.version,
+        style(&filename).dim()
+    );
+    let (pb, progress) = upload::progress_bar("Artifact");
+    let downloaded = upload::download_file(
+        &download.download_url,
+        &tarball_path,
+        download.sha256.as_deref(),
+        Some(progress),
+    )
+    .await;
+    pb.finish_and_clear();
+    let sha256 = downloaded?.sha256_hex();
+
+    match unpack_dir {
+        Some(dir) => {
+            let unpacked = manifest::unpack_artifact(&tarball_path, &dir);
+            std::fs::remove_file(&tarball_path)?;
+            unpacked?;
+            utils::print_success(&format!("Artifact unpacked into {}", dir.display()));
+        }
+        None => {
+            utils::print_success(&format!("Artifact saved to {}", tarball_path.display()));
+        }
+    }
+    println!("🚀 Deployment ID: {}", style(deployment.id).dim());
+    println!("🔐 SHA-256: {}", style(&sha256).dim());
+
+    Ok(())
+}
+
+async fn artifact_delete_command(app: String, sha256: String, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to delete artifacts");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+    let deployed = deployed_artifact_urls(&client, app_id).await?;
+
+    let store = artifact_store::open(&config, client, None, None).await?;
+    let prefix = sha256.to_lowercase();
+    let mut matches: Vec<_> = store
+        .list_artifacts(app_id)
+        .await?
+        .into_iter()
+        .filter(|artifact| artifact.sha256.starts_with(&prefix))
+        .collect();
+    let artifact = match matches.len() {
+        1 => matches.remove(0),
+        0 => {
+            return Err(crate::AetherError::Other(format!(
+                "No artifact with SHA-256 {} stored for app '{}'",
+                sha256, app_details.name
+            )))
+        }
+        n => {
+            return Err(crate::AetherError::Other(format!(
+                "{} artifacts match SHA-256 {}, give more of it",
+                n, sha256
+            )))
+        }
+    };
+    if deployed
+        .iter()
+        .any(|url| artifact_store::is_same_artifact(&artifact, url))
+    {
+        return Err(crate::AetherError::Other(format!(
+            "Artifact {} is used by a live deployment of '{}' and cannot be deleted",
+            short_sha(&artifact.sha256),
+            app_details.name
+        )));
+    }
+
+    if !yes {
+        let confirmed = utils::confirm(&format!(
+            "Are you sure you want to delete artifact {} ({})? This action cannot be undone.",
+            short_sha(&artifact.sha256),
+            utils::format_size(artifact.size)
+        ))?;
+
+        if !confirmed {
+            utils::print_info("Deletion cancelled");
+            return Ok(());
+        }
+    }
+
+    store.delete_artifact(app_id, &artifact).await?;
+
+    utils::print_success(&format!("Artifact {} deleted", short_sha(&artifact.sha256)));
+
+    Ok(())
+}
+
+async fn artifact_prune_command(app: String, keep: usize, dry_run: bool, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to prune artifacts");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint.clone(), config.auth_token.clone())?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+    let deployed = deployed_artifact_urls(&client, app_id).await?;
+
+    let store = artifact_store::open(&config, client, None, None).await?;
+    let deployed: Vec<&str> = deployed.iter().map(String::as_str).collect();
+    let (kept, pruned) =
+        artifact_store::retention(store.list_artifacts(app_id).await?, keep, &deployed);
+    if pruned.is_empty() {
+        utils::print_info(&format!(
+            "Nothing to prune, '{}' has {} stored artifacts",
+            app_details.name,
+            kept.len()
+        ));
+        return Ok(());
+    }
+
+    let total = utils::format_size(pruned.iter().map(|artifact| artifact.size).sum());
+    println!(
+        "🧹 {}",
+        style(format!(
+            "Pruning {} of {} artifacts for '{}' ({})",
+            pruned.len(),
+            kept.len() + pruned.len(),
+            app_details.name,
+            total
+        ))
+        .bold()
+    );
+    for artifact in &pruned {
+        println!(
+            "  {} {:<16} {:>10}  {}",
+            style(short_sha(&artifact.sha256)).cyan(),
+            artifact.version.as_deref().unwrap_or("-"),
+            utils::format_size(artifact.size),
+            artifact
+                .created_at
+                .map(|created| created.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "-".to_string())
+        );
+    }
+    if dry_run {
+        utils::print_info("Dry run, nothing was deleted");
+        return Ok(());
+    }
+
+    if !yes {
+        let confirmed = utils::confirm(&format!(
+            "Delete these {} artifacts? This action cannot be undone.",
+            pruned.len()
+        ))?;
+
+        if !confirmed {
+            utils::print_info("Pruning cancelled");
+            return Ok(());
+        }
+    }
+
+    for artifact in &pruned {
+        store.delete_artifact(app_id, artifact).await?;
+    }
+
+    utils::print_success(&format!(
+        "Deleted {} artifacts, freeing {}",
+        pruned.len(),
+        total
+    ));
+
+    Ok(())
+}
+
+/// The latest deployment of `app_id` whose rollout is still in progress.
+async fn rollout_in_progress(client: &ApiClient, app_id: Uuid, app: &str) -> Result<Deployment> {
+    client
+        .list_deployments(app_id)
+        .await?
+        .into_iter()
+        .filter(|deployment| {
+            deployment
+                .rollout
+                .as_ref()
+                .is_some_and(|rollout| rollout.state == RolloutState::InProgress)
+        })
+        .max_by_key(|deployment| deployment.created_at)
+        .ok_or_else(|| crate::AetherError::Other(format!("'{}' has no rollout in progress", app)))
+}
+
+async fn promote_command(app: String, all: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to promote deployments");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+    let mut deployment = rollout_in_progress(&client, app_id, &app_details.name).await?;
+
+    println!(
+        "⏩ Promoting {} {}...",
+        style(&app_details.name).cyan(),
+        deployment.version
+    );
+    loop {
+        deployment = client.promote_deployment(app_id, deployment.id).await?;
+        let Some(state) = &deployment.rollout else {
+            break;
+        };
+        println!("{}", rollout::describe(state));
+        if !all || state.state != RolloutState::InProgress {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+async fn abort_command(app: String, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to abort deployments");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+    let deployment = rollout_in_progress(&client, app_id, &app_details.name).await?;
+
+    if !yes {
+        let confirmed = utils::confirm(&format!(
+            "Abort the rollout of {} {} and send all traffic back to the previous deployment?",
+            app_details.name, deployment.version
+        ))?;
+
+        if !confirmed {
+            utils::print_info("Abort cancelled");
+            return Ok(());
+        }
+    }
+
+    let deployment = client.abort_deployment(app_id, deployment.id).await?;
+    if let Some(state) = &deployment.rollout {
+        println!("{}", rollout::describe(state));
+    }
+
+    Ok(())
+}
+
+async fn preview_command(action: PreviewCommands) -> Result<()> {
+    match action {
+        PreviewCommands::List { app } => preview_list_command(app).await,
+        PreviewCommands::Delete { app, branch, yes } => {
+            preview_delete_command(app, branch, yes).await
+        }
+    }
+}
+
+async fn preview_list_command(app: String) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to list previews");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+
+    println!(
+        "🔍 {}",
+        style(format!("Previews of '{}'", app_details.name)).bold()
+    );
+
+    let previews = list_previews(&client, app_id).await?;
+    if previews.is_empty() {
+        utils::print_info("No previews deployed");
+        utils::print_info("Deploy one from a branch with: aether deploy --preview");
+        return Ok(());
+    }
+
+    // Branches are checked against the repository in the current directory
+    let branches = std::env::current_dir()
+        .ok()
+        .and_then(|dir| preview::live_branches(&dir));
+
+    println!(
+        "\n{:<30} {:<30} {:<20} {:<40}",
+        "BRANCH", "NAME", "UPDATED", "URL"
+    );
+    println!("{}", "─".repeat(120));
+    for preview in &previews {
+        let branch = preview.preview_branch.as_deref().unwrap_or("-");
+        let gone = branches
+            .as_ref()
+            .is_some_and(|branches| !branches.contains(branch));
+        println!(
+            "{:<30} {:<30} {:<20} {:<40}",
+            if gone {
+                style(format!("{} (gone)", branch)).yellow().to_string()
+            } else {
+                style(branch).cyan().to_string()
+            },
+            preview.name,
+            preview.updated_at.format("%Y-%m-%d %H:%M"),
+            preview.deployment_url.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
+
+async fn preview_delete_command(app: String, branch: Option<String>, yes: bool) -> Result<()> {
+    let config = Config::load()?;
+
+    // Check authentication first
+    if !config.is_authenticated() {
+        utils::print_error("❌ Authentication required to delete previews");
+        utils::print_info("Please login first: aether login --email your@email.com");
+        return Ok(());
+    }
+
+    let branch = match branch {
+        Some(branch) => branch,
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|dir| preview::current_branch(&dir))
+            .ok_or_else(|| {
+                crate::AetherError::Other(
+                    "Not on a git branch, pass the preview's branch with --branch".to_string(),
+                )
+            })?,
+    };
+
+    let client = ApiClient::new(config.api_endpoint, config.auth_token)?;
+
+    // Find application by name or UUID
+    let app_id = resolve_app_identifier(&client, &app).await?;
+    let app_details = client.get_application(app_id).await?;
+
+    let preview = list_previews(&client, app_id)
+        .await?
+        .into_iter()
+        .find(|preview| preview.preview_branch.as_deref() == Some(branch.as_str()))
+        .ok_or_else(|| {
+            crate::AetherError::Other(format!(
+                "'{}' has no preview of branch {}",
+                app_details.name, branch
+            ))
+        })?;
+
+    if !yes {
+        let confirmed = utils::confirm(&format!(
+            "Are you sure you want to delete preview '{}' of branch {}?",
+            preview.name, branch
+        ))?;
+
+        if !confirmed {
+            utils::print_info("Deletion cancelled");
+            return Ok(());
+        }
+    }
+
+    println!("🗑️  Deleting preview '{}'...", preview.name);
+
+    client.delete_application(preview.id).await?;
+
+    utils::print_success(&format!("Preview '{}' deleted successfully", preview.name));
+
+    Ok(())
+}
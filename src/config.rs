@@ -8,6 +8,30 @@ pub struct Config {
     pub auth_token: Option<String>,
     pub default_runtime: String,
     pub build_timeout: u64,
+    /// Where artifacts are uploaded
+    #[serde(default)]
+    pub artifact_store: ArtifactStoreConfig,
+}
+
+/// Storage artifacts are uploaded to, see [`crate::artifact_store`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ArtifactStoreConfig {
+    /// Storage of the platform, through presigned URLs it hands out
+    #[default]
+    Presigned,
+    /// An S3 bucket written to directly, e.g. on MinIO or Storj for
+    /// self-hosted platforms. Credentials come from the usual AWS sources.
+    S3 {
+        bucket: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        region: Option<String>,
+        /// Endpoint of an S3-compatible service
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+    },
+    /// A directory, e.g. one a platform on the same machine reads from
+    Local { path: PathBuf },
 }
 
 impl Default for Config {
@@ -17,6 +41,7 @@ impl Default for Config {
             auth_token: None,
             default_runtime: "node:20".to_string(),
             build_timeout: 300, // 5 minutes
+            artifact_store: ArtifactStoreConfig::default(),
         }
     }
 }
@@ -51,9 +76,24 @@ impl Config {
     }
 
     fn config_path() -> Result<PathBuf> {
+        Ok(Self::aether_dir()?.join("config.json"))
+    }
+
+    /// The `~/.aether` directory holding CLI configuration and state.
+    pub fn aether_dir() -> Result<PathBuf> {
         let home = std::env::var("HOME")
             .map_err(|_| crate::AetherError::config("HOME environment variable not set"))?;
-        Ok(PathBuf::from(home).join(".aether").join("config.json"))
+        Ok(PathBuf::from(home).join(".aether"))
+    }
+
+    /// Directory where build logs are written.
+    pub fn logs_dir() -> Result<PathBuf> {
+        Ok(Self::aether_dir()?.join("logs"))
+    }
+
+    /// Directory holding the state of interrupted multipart uploads.
+    pub fn uploads_dir() -> Result<PathBuf> {
+        Ok(Self::aether_dir()?.join("uploads"))
     }
 
     pub fn set_auth_token(&mut self, token: String) -> Result<()> {
@@ -22,6 +22,12 @@ pub enum AetherError {
     #[error("Build error: {0}")]
     Build(String),
 
+    #[error("Build timed out after {timeout_secs}s (build log: {})", log.display())]
+    BuildTimeout {
+        timeout_secs: u64,
+        log: std::path::PathBuf,
+    },
+
     #[error("Deployment error: {0}")]
     Deployment(String),
 
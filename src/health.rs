@@ -0,0 +1,202 @@
+//! Health checks gating a deploy: the new deployment must answer its health
+//! path successfully several times in a row before the deploy counts as
+//! done.
+
+use crate::project_config::HealthCheckConfig;
+use crate::{utils, AetherError, Result};
+use std::time::{Duration, Instant};
+
+/// Longest a single check may take.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    /// Path requested on the deployment URL
+    pub path: String,
+    /// Successful checks in a row that make the deployment healthy
+    pub successes: u32,
+    pub interval: Duration,
+    /// Time the deployment has to become healthy
+    pub timeout: Duration,
+}
+
+/// Outcome of the checks run so far.
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub checks: u32,
+    pub failures: u32,
+    /// Successful checks since the last failure
+    pub consecutive: u32,
+    /// Why the last failed check failed
+    pub last_failure: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl HealthCheck {
+    /// Check `path` with the default count, interval and timeout.
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Self {
+            path: path.into(),
+            successes: 3,
+            interval: Duration::from_secs(5),
+            timeout: Duration::from_secs(2 * 60),
+        }
+    }
+
+    /// The checks configured by the `health_check` section of aether.json.
+    pub fn from_config(config: &HealthCheckConfig) -> Result<Self> {
+        let duration = |value: &Option<String>, field: &str| {
+            value
+                .as_deref()
+                .map(utils::parse_duration)
+                .transpose()
+                .map_err(|e| AetherError::config(format!("Invalid health_check.{}: {}", field, e)))
+        };
+        if config.path.trim().is_empty() {
+            return Err(AetherError::config("health_check.path must not be empty"));
+        }
+        let mut check = Self::new(&config.path);
+        if let Some(successes) = config.successes {
+            if successes == 0 {
+                return Err(AetherError::config(
+                    "health_check.successes must be at least 1",
+                ));
+            }
+            check.successes = successes;
+        }
+        if let Some(interval) = duration(&config.interval, "interval")? {
+            check.interval = interval;
+        }
+        if let Some(timeout) = duration(&config.timeout, "timeout")? {
+            check.timeout = timeout;
+        }
+        Ok(check)
+    }
+
+    pub fn with_path<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// URL checked for a deployment served at `deployment_url`.
+    pub fn url(&self, deployment_url: &str) -> String {
+        format!(
+            "{}/{}",
+            deployment_url.trim_end_matches('/'),
+            self.path.trim_start_matches('/')
+        )
+    }
+
+    /// Request the health URL of `deployment_url` every interval until
+    /// enough checks in a row succeed or the timeout passes, reporting
+    /// each check to `on_check`.
+    pub async fn run<F: FnMut(&HealthReport)>(
+        &self,
+        deployment_url: &str,
+        mut on_check: F,
+    ) -> Result<HealthReport> {
+        let url = self.url(deployment_url);
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT.min(self.timeout.max(Duration::from_secs(1))))
+            .build()?;
+        let started = Instant::now();
+        let mut report = HealthReport::default();
+        loop {
+            report.checks += 1;
+            match client.get(&url).send().await {
+                Ok(response) if response.status().is_success() => report.consecutive += 1,
+                Ok(response) => {
+                    report.failures += 1;
+                    report.consecutive = 0;
+                    report.last_failure = Some(format!("HTTP {}", response.status()));
+                }
+                Err(e) => {
+                    report.failures += 1;
+                    report.consecutive = 0;
+                    report.last_failure = Some(e.without_url().to_string());
+                }
+            }
+            report.elapsed = started.elapsed();
+            report.healthy = report.consecutive >= self.successes;
+            on_check(&report);
+
+            if report.healthy || report.elapsed + self.interval > self.timeout {
+                return Ok(report);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve `statuses` to one request each, then close.
+    async fn serve(statuses: Vec<u16>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_health_check_needs_consecutive_successes() {
+        let mut check = HealthCheck::new("/healthz");
+        check.interval = Duration::from_millis(10);
+        check.successes = 2;
+
+        let url = serve(vec![200, 503, 200, 200]).await;
+        let report = check.run(&url, |_| {}).await.unwrap();
+        assert!(report.healthy);
+        assert_eq!(report.checks, 4);
+        assert_eq!(report.failures, 1);
+        assert_eq!(
+            report.last_failure.as_deref(),
+            Some("HTTP 503 Service Unavailable")
+        );
+
+        let url = serve(vec![500; 3]).await;
+        let check = check.with_timeout(Duration::from_millis(25));
+        let report = check.run(&url, |_| {}).await.unwrap();
+        assert!(!report.healthy);
+        assert_eq!(report.failures, report.checks);
+    }
+
+    #[test]
+    fn test_health_check_config_needs_path() {
+        let config = |json: &str| {
+            serde_json::from_str::<crate::project_config::ProjectConfig>(json)
+                .map(|config| config.health_check.unwrap())
+        };
+        assert!(config(r#"{"health_check": {"successes": 2}}"#).is_err());
+        assert!(
+            HealthCheck::from_config(&config(r#"{"health_check": {"path": ""}}"#).unwrap())
+                .is_err()
+        );
+
+        let check =
+            HealthCheck::from_config(&config(r#"{"health_check": {"path": "/healthz"}}"#).unwrap())
+                .unwrap();
+        assert_eq!(check.path, "/healthz");
+        assert_eq!(check.successes, 3);
+    }
+}
@@ -1,14 +1,24 @@
 pub mod api;
+pub mod artifact_store;
 pub mod builder;
 pub mod commands;
 pub mod config;
 // pub mod dashboard;  // Disabled old dashboard
 pub mod error;
+pub mod health;
+pub mod manifest;
+pub mod platform;
 pub mod pokemon_theme;
 pub mod pokemon_widgets;
 pub mod presigned_uploader;
+pub mod preview;
+pub mod project_config;
+pub mod rollout;
+pub mod runtime;
 pub mod s3_uploader;
 pub mod terminal_dashboard;
+pub mod upload;
 pub mod utils;
+pub mod workspace;
 
 pub use error::{AetherError, Result};
@@ -28,6 +28,13 @@ async fn main() -> aether_cli::Result<()> {
 
     let cli = Cli::parse();
 
+    // Build tools run in their own process group, so stop them before exiting
+    tokio::spawn(async {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            aether_cli::builder::handle_interrupt();
+        }
+    });
+
     if let Err(e) = execute_command(cli).await {
         aether_cli::utils::print_error(&format!("Error: {}", e));
         std::process::exit(1);
@@ -0,0 +1,253 @@
+use crate::{AetherError, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Name of the manifest at the root of every tarball artifact.
+pub const MANIFEST_FILE: &str = "aether-manifest.json";
+
+/// Lockfiles whose hash identifies the installed dependencies, in the
+/// order they are looked for.
+const LOCKFILES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "bun.lockb",
+    "deno.lock",
+    "Pipfile.lock",
+    "poetry.lock",
+    "uv.lock",
+    "requirements.txt",
+];
+
+/// Record of what an artifact contains and where it came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    pub app: String,
+    pub version: String,
+    pub runtime: String,
+    pub cli_version: String,
+    /// Source date of the build, `SOURCE_DATE_EPOCH` or the last commit's
+    /// time, rather than the clock
+    pub built_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lockfile: Option<LockfileInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>,
+    /// Key of the dependency layer deployed together with these files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency_layer: Option<String>,
+    /// SHA-256 of every file, keyed by its path in the artifact
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockfileInfo {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitInfo {
+    pub commit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub dirty: bool,
+}
+
+impl LockfileInfo {
+    /// Hash the first lockfile found in `root`.
+    pub fn find(root: &Path) -> Result<Option<Self>> {
+        let Some(path) = LOCKFILES.iter().find(|file| root.join(file).is_file()) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            path: path.to_string(),
+            sha256: crate::utils::sha256_file(&root.join(path))?,
+        }))
+    }
+}
+
+impl GitInfo {
+    /// Commit, branch and working tree state of the repository containing
+    /// `path`, or `None` outside a git repository.
+    pub fn collect(path: &Path) -> Option<Self> {
+        let commit = git(path, &["rev-parse", "HEAD"])?;
+        // Detached checkouts report "HEAD" instead of a branch
+        let branch =
+            git(path, &["rev-parse", "--abbrev-ref", "HEAD"]).filter(|branch| branch != "HEAD");
+        let dirty = git(path, &["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
+        Some(Self {
+            commit,
+            branch,
+            dirty,
+        })
+    }
+}
+
+/// Output of `git` run with `args` in `path`, or `None` if it fails.
+pub(crate) fn git(path: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Result of checking an artifact's files against its manifest.
+#[derive(Debug)]
+pub struct Verification {
+    pub manifest: ArtifactManifest,
+    /// Files whose content no longer matches the recorded hash
+    pub modified: Vec<String>,
+    /// Files listed in the manifest but absent from the artifact
+    pub missing: Vec<String>,
+    /// Files in the artifact the manifest does not list
+    pub unexpected: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_valid(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Open a gzip or zstd compressed tarball, telling them apart by their
+/// magic bytes.
+fn decompress(path: &Path) -> Result<Box<dyn Read>> {
+    const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+    let mut reader = BufReader::new(File::open(path)?);
+    if reader.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+        Ok(Box::new(zstd::Decoder::with_buffer(reader)?))
+    } else {
+        Ok(Box::new(GzDecoder::new(reader)))
+    }
+}
+
+/// Extract the tarball artifact at `path` into `dest`.
+pub fn unpack_artifact(path: &Path, dest: &Path) -> Result<()> {
+    std::fs::create_dir_all(dest)?;
+    tar::Archive::new(decompress(path)?).unpack(dest)?;
+    Ok(())
+}
+
+/// Hash every file in the tarball at `path` and compare the result with
+/// its embedded manifest.
+pub fn verify_artifact(path: &Path) -> Result<Verification> {
+    let mut archive = tar::Archive::new(decompress(path)?);
+    let mut manifest = None;
+    let mut hashes = BTreeMap::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?;
+        let name = path
+            .strip_prefix(".")
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        if name == MANIFEST_FILE {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            manifest = Some(serde_json::from_str::<ArtifactManifest>(&content)?);
+        } else {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut entry, &mut hasher)?;
+            hashes.insert(name, format!("{:x}", hasher.finalize()));
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| {
+        AetherError::invalid_project(format!("No {} found in {}", MANIFEST_FILE, path.display()))
+    })?;
+
+    let mut modified = Vec::new();
+    let mut missing = Vec::new();
+    for (file, expected) in &manifest.files {
+        match hashes.remove(file) {
+            Some(actual) if &actual != expected => modified.push(file.clone()),
+            Some(_) => {}
+            None => missing.push(file.clone()),
+        }
+    }
+
+    Ok(Verification {
+        manifest,
+        modified,
+        missing,
+        unexpected: hashes.into_keys().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    fn sha256(content: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(content))
+    }
+
+    #[test]
+    fn test_verify_artifact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.tar.gz");
+        let manifest = ArtifactManifest {
+            app: "app".to_string(),
+            version: "1.0.0".to_string(),
+            runtime: "node:20".to_string(),
+            cli_version: "1.2.0".to_string(),
+            built_at: Utc::now(),
+            lockfile: None,
+            git: None,
+            dependency_layer: None,
+            files: BTreeMap::from([
+                ("index.js".to_string(), sha256(b"console.log(1)")),
+                ("lib/util.js".to_string(), sha256(b"original")),
+                ("README.md".to_string(), sha256(b"docs")),
+            ]),
+        };
+
+        let mut tar = tar::Builder::new(GzEncoder::new(
+            File::create(&path).unwrap(),
+            Compression::default(),
+        ));
+        let manifest_json = serde_json::to_vec(&manifest).unwrap();
+        for (name, content) in [
+            ("index.js", b"console.log(1)".as_slice()),
+            ("lib/util.js", b"tampered"),
+            ("extra.js", b"injected"),
+            (MANIFEST_FILE, &manifest_json),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            tar.append_data(&mut header, name, content).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let verification = verify_artifact(&path).unwrap();
+        assert!(!verification.is_valid());
+        assert_eq!(verification.modified, ["lib/util.js"]);
+        assert_eq!(verification.missing, ["README.md"]);
+        assert_eq!(verification.unexpected, ["extra.js"]);
+    }
+}
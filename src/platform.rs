@@ -0,0 +1,332 @@
+use crate::Result;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Operating systems, named as Node.js `process.platform` names them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Os {
+    Linux,
+    Darwin,
+    Win32,
+}
+
+/// CPU architectures, named as Node.js `process.arch` names them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X64,
+    Arm64,
+    Ia32,
+    Arm,
+}
+
+/// An OS and CPU architecture pair such as `linux-x64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Platform {
+    pub os: Os,
+    pub arch: Arch,
+}
+
+impl Platform {
+    /// Platform applications run on after deployment.
+    pub const RUNTIME: Platform = Platform {
+        os: Os::Linux,
+        arch: Arch::X64,
+    };
+
+    /// Platform the CLI is running on, if it is one applications can be
+    /// built on.
+    pub fn host() -> Option<Self> {
+        let os = match std::env::consts::OS {
+            "linux" => Os::Linux,
+            "macos" => Os::Darwin,
+            "windows" => Os::Win32,
+            _ => return None,
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => Arch::X64,
+            "aarch64" => Arch::Arm64,
+            "x86" => Arch::Ia32,
+            "arm" => Arch::Arm,
+            _ => return None,
+        };
+        Some(Self { os, arch })
+    }
+
+    pub fn os_name(self) -> &'static str {
+        match self.os {
+            Os::Linux => "linux",
+            Os::Darwin => "darwin",
+            Os::Win32 => "win32",
+        }
+    }
+
+    pub fn arch_name(self) -> &'static str {
+        match self.arch {
+            Arch::X64 => "x64",
+            Arch::Arm64 => "arm64",
+            Arch::Ia32 => "ia32",
+            Arch::Arm => "arm",
+        }
+    }
+
+    /// Wheel platform tags pip accepts for this platform.
+    pub fn python_platform_tags(self) -> Vec<String> {
+        let machine = match (self.os, self.arch) {
+            (Os::Linux, Arch::X64) | (Os::Darwin, Arch::X64) => "x86_64",
+            (Os::Linux, Arch::Arm64) => "aarch64",
+            (Os::Darwin, Arch::Arm64) => "arm64",
+            (Os::Linux, Arch::Ia32) => "i686",
+            (Os::Linux, Arch::Arm) => "armv7l",
+            (Os::Win32, Arch::X64) => "amd64",
+            (Os::Win32, Arch::Arm64) => "arm64",
+            (Os::Win32, _) => "win32",
+            (Os::Darwin, _) => "universal2",
+        };
+        match self.os {
+            Os::Linux => vec![
+                format!("manylinux2014_{}", machine),
+                format!("manylinux_2_28_{}", machine),
+                format!("linux_{}", machine),
+            ],
+            Os::Darwin => vec![format!("macosx_11_0_{}", machine)],
+            Os::Win32 if machine == "win32" => vec!["win32".to_string()],
+            Os::Win32 => vec![format!("win_{}", machine)],
+        }
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.os_name(), self.arch_name())
+    }
+}
+
+impl FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid platform '{}', expected <os>-<arch> such as linux-x64 or linux-arm64",
+                s
+            )
+        };
+        let (os, arch) = s.split_once(['-', '/']).ok_or_else(invalid)?;
+        let os = match os {
+            "linux" => Os::Linux,
+            "darwin" | "macos" => Os::Darwin,
+            "win32" | "windows" => Os::Win32,
+            _ => return Err(invalid()),
+        };
+        let arch = match arch {
+            "x64" | "amd64" | "x86_64" => Arch::X64,
+            "arm64" | "aarch64" => Arch::Arm64,
+            "ia32" | "x86" => Arch::Ia32,
+            "arm" => Arch::Arm,
+            _ => return Err(invalid()),
+        };
+        Ok(Self { os, arch })
+    }
+}
+
+/// A native binary shipped in an artifact that cannot load on the
+/// target platform.
+#[derive(Debug)]
+pub struct ForeignBinary {
+    /// Path relative to the packaged directory
+    pub path: PathBuf,
+    /// Platforms the binary was built for
+    pub platforms: Vec<Platform>,
+}
+
+/// Native addons and shared libraries among `entries` (relative to
+/// `work_dir`) that were built for a platform other than `target`.
+/// Binaries in `prebuilds/<platform>` directories are ignored, since
+/// packages ship those for every platform and pick one at runtime.
+pub fn find_foreign_binaries(
+    work_dir: &Path,
+    entries: &[PathBuf],
+    target: Platform,
+) -> Result<Vec<ForeignBinary>> {
+    let mut foreign = Vec::new();
+    for entry in entries.iter().filter(|entry| is_native_binary_name(entry)) {
+        if is_other_prebuild(entry, target) {
+            continue;
+        }
+        let full_path = work_dir.join(entry);
+        if !full_path.is_file() {
+            continue;
+        }
+
+        let mut header = Vec::with_capacity(4096);
+        std::fs::File::open(&full_path)?
+            .take(4096)
+            .read_to_end(&mut header)?;
+        if let Some(platforms) = binary_platforms(&header) {
+            if !platforms.contains(&target) {
+                foreign.push(ForeignBinary {
+                    path: entry.clone(),
+                    platforms,
+                });
+            }
+        }
+    }
+    Ok(foreign)
+}
+
+fn is_native_binary_name(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    [".node", ".so", ".dylib", ".dll"]
+        .iter()
+        .any(|extension| name.ends_with(extension))
+        || name.contains(".so.")
+}
+
+fn is_other_prebuild(path: &Path, target: Platform) -> bool {
+    let components: Vec<_> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.windows(2).any(|pair| {
+        pair[0] == "prebuilds"
+            && pair[1]
+                .parse::<Platform>()
+                .is_ok_and(|platform| platform != target)
+    })
+}
+
+/// Platforms an ELF, Mach-O (including universal) or PE binary was built
+/// for, judged from its header. `None` for other files.
+pub fn binary_platforms(header: &[u8]) -> Option<Vec<Platform>> {
+    let u16_at = |offset: usize, little_endian: bool| {
+        let bytes: [u8; 2] = header.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize, little_endian: bool| {
+        let bytes: [u8; 4] = header.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+    let mach_arch = |cpu_type: u32| match cpu_type {
+        0x0100_0007 => Some(Arch::X64),
+        0x0100_000c => Some(Arch::Arm64),
+        0x0000_0007 => Some(Arch::Ia32),
+        0x0000_000c => Some(Arch::Arm),
+        _ => None,
+    };
+    let platform = |os, arch: Option<Arch>| arch.map(|arch| vec![Platform { os, arch }]);
+
+    match header.get(..4)? {
+        [0x7f, b'E', b'L', b'F'] => {
+            let little_endian = header.get(5)? == &1;
+            let arch = match u16_at(18, little_endian)? {
+                0x3e => Some(Arch::X64),
+                0xb7 => Some(Arch::Arm64),
+                0x03 => Some(Arch::Ia32),
+                0x28 => Some(Arch::Arm),
+                _ => None,
+            };
+            platform(Os::Linux, arch)
+        }
+        [0xcf, 0xfa, 0xed, 0xfe] | [0xce, 0xfa, 0xed, 0xfe] => {
+            platform(Os::Darwin, mach_arch(u32_at(4, true)?))
+        }
+        [0xca, 0xfe, 0xba, 0xbe] => {
+            // Universal binary: a big-endian list of 20-byte arch entries
+            let count = u32_at(4, false)? as usize;
+            if count == 0 || count > 16 {
+                return None;
+            }
+            let platforms = (0..count)
+                .filter_map(|i| mach_arch(u32_at(8 + i * 20, false)?))
+                .map(|arch| Platform {
+                    os: Os::Darwin,
+                    arch,
+                })
+                .collect::<Vec<_>>();
+            (!platforms.is_empty()).then_some(platforms)
+        }
+        [b'M', b'Z', ..] => {
+            let pe_offset = u32_at(0x3c, true)? as usize;
+            if header.get(pe_offset..pe_offset + 4)? != b"PE\0\0" {
+                return None;
+            }
+            let arch = match u16_at(pe_offset + 4, true)? {
+                0x8664 => Some(Arch::X64),
+                0xaa64 => Some(Arch::Arm64),
+                0x014c => Some(Arch::Ia32),
+                0x01c4 => Some(Arch::Arm),
+                _ => None,
+            };
+            platform(Os::Win32, arch)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_platforms() {
+        let mut elf = vec![0x7f, b'E', b'L', b'F', 2, 1];
+        elf.resize(20, 0);
+        elf[18] = 0xb7;
+        assert_eq!(
+            binary_platforms(&elf),
+            Some(vec!["linux-arm64".parse().unwrap()])
+        );
+
+        let mut mach_o = vec![0xcf, 0xfa, 0xed, 0xfe];
+        mach_o.extend(0x0100_000c_u32.to_le_bytes());
+        assert_eq!(
+            binary_platforms(&mach_o),
+            Some(vec!["darwin-arm64".parse().unwrap()])
+        );
+
+        let mut universal = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2];
+        for cpu_type in [0x0100_0007_u32, 0x0100_000c] {
+            universal.extend(cpu_type.to_be_bytes());
+            universal.extend([0; 16]);
+        }
+        assert_eq!(
+            binary_platforms(&universal),
+            Some(vec![
+                "darwin-x64".parse().unwrap(),
+                "darwin-arm64".parse().unwrap()
+            ])
+        );
+
+        assert_eq!(binary_platforms(b"#!/usr/bin/env node"), None);
+    }
+
+    #[test]
+    fn test_is_other_prebuild() {
+        let target = Platform::RUNTIME;
+        assert!(is_other_prebuild(
+            Path::new("node_modules/pty/prebuilds/darwin-arm64/pty.node"),
+            target
+        ));
+        assert!(!is_other_prebuild(
+            Path::new("node_modules/pty/prebuilds/linux-x64/pty.node"),
+            target
+        ));
+        assert!(!is_other_prebuild(
+            Path::new("node_modules/@img/sharp-darwin-arm64/lib/sharp.node"),
+            target
+        ));
+    }
+}
@@ -1,62 +1,930 @@
-use crate::{api::ApiClient, Result};
+use crate::{
+    api::{
+        AbortMultipartUploadRequest, ApiClient, ArtifactDownload, CompleteMultipartUploadRequest,
+        CompletedPart, CompressionFormat, CreateMultipartUploadRequest, DependencyLayerRef,
+        GeneratePresignedDownloadUrlRequest, GeneratePresignedUrlRequest, MultipartPartRequest,
+        MultipartPartUrl, MultipartPartUrlsRequest, StoredArtifact,
+    },
+    artifact_store::{ArtifactFormat, ArtifactStore, UploadedFile},
+    config::Config,
+    upload::{self, ChecksumHasher, Checksums, OutputCallback, ProgressCallback, UploadStats},
+    AetherError, Result,
+};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::StreamExt;
 use reqwest;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Files at least this large are uploaded in parts.
+const MULTIPART_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// Smallest part size used. S3 requires at least 5 MiB for every part but
+/// the last.
+const MIN_PART_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Most parts S3 accepts in a single upload.
+const MAX_PARTS: u64 = 10_000;
+
+/// Parts uploaded at the same time.
+const PART_CONCURRENCY: usize = 4;
+
+/// Attempts per part before the upload is left to be resumed later.
+const PART_ATTEMPTS: u32 = 4;
+
+/// Interrupted uploads older than this are aborted instead of resumed.
+const STALE_UPLOAD_DAYS: i64 = 7;
+
+/// A file about to be uploaded, with the checksums of its content and,
+/// when it is uploaded in parts, of each part.
+struct HashedFile<'a> {
+    path: &'a Path,
+    size: u64,
+    checksums: Checksums,
+    part_checksums: Vec<Checksums>,
+}
+
+impl<'a> HashedFile<'a> {
+    async fn hash(path: &'a Path) -> Result<Self> {
+        let size = std::fs::metadata(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+            .len();
+        // Parts are hashed in the same read as the whole file
+        let (checksums, part_checksums) = if size >= MULTIPART_THRESHOLD {
+            Checksums::of_parts(path, part_size_for(size)).await?
+        } else {
+            (Checksums::of_file(path).await?, Vec::new())
+        };
+        Ok(Self {
+            path,
+            size,
+            checksums,
+            part_checksums,
+        })
+    }
+}
+
 pub struct PresignedUploader {
     client: ApiClient,
+    http: reqwest::Client,
+    progress: Option<ProgressCallback>,
+    output_callback: Option<OutputCallback>,
 }
 
 impl PresignedUploader {
     pub fn new(client: ApiClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            http: reqwest::Client::new(),
+            progress: None,
+            output_callback: None,
+        }
+    }
+
+    pub fn with_output_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.output_callback = Some(Box::new(callback));
+        self
+    }
+
+    fn output(&self, message: &str) {
+        if let Some(ref callback) = self.output_callback {
+            callback(message);
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    /// Report the bytes sent by each upload to `callback`.
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
+        self
     }
 
-    pub async fn upload_artifact(
+    /// Upload `path`, or with `reuse_stored` skip the upload when the
+    /// platform already stores identical content for the application.
+    async fn upload_file(
         &self,
-        artifact_path: &Path,
+        path: &Path,
         app_id: Uuid,
         version: &str,
-    ) -> Result<(String, String)> {
-        // Step 1: Get presigned URL from backend
-        let filename = artifact_path
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("artifact.tar.gz");
+        filename: &str,
+        content_type: &str,
+        reuse_stored: bool,
+    ) -> Result<UploadedFile> {
+        let file = HashedFile::hash(path).await?;
+        let sha256 = file.checksums.sha256_hex();
+        if reuse_stored {
+            if let Some(stored) = self.client.find_artifact(app_id, &sha256).await? {
+                return Ok(UploadedFile {
+                    key: stored.artifact_url,
+                    sha256,
+                    reused: true,
+                });
+            }
+        }
+
+        let started = Instant::now();
+        let key = if file.size >= MULTIPART_THRESHOLD {
+            self.upload_multipart(&file, app_id, version, filename, content_type)
+                .await?
+        } else {
+            self.upload_single(&file, app_id, version, filename, content_type)
+                .await?
+        };
+        UploadStats::record(file.size, started.elapsed());
 
+        Ok(UploadedFile {
+            key,
+            sha256,
+            reused: false,
+        })
+    }
 
+    /// Upload a file in a single PUT, returning its key.
+    async fn upload_single(
+        &self,
+        file: &HashedFile<'_>,
+        app_id: Uuid,
+        version: &str,
+        filename: &str,
+        content_type: &str,
+    ) -> Result<String> {
+        let (path, size, checksums) = (file.path, file.size, &file.checksums);
+
+        // Step 1: Get a presigned URL for the file's checksums from backend
         let presigned_response = self
             .client
-            .get_presigned_upload_url(app_id, version, filename)
+            .get_presigned_upload_url(GeneratePresignedUrlRequest {
+                app_id,
+                version: version.to_string(),
+                filename: filename.to_string(),
+                checksum_sha256: checksums.sha256_base64(),
+                content_md5: checksums.md5_base64(),
+            })
             .await?;
 
-      
-
-        let file_content = std::fs::read(artifact_path)
-            .map_err(|e| anyhow::anyhow!("Failed to read artifact file: {}", e))?;
-
-        let client = reqwest::Client::new();
-        let response = client
-            .put(&presigned_response.upload_url)
-            .header("Content-Type", "application/gzip")
-            .body(file_content)
-            .send()
+        // Step 2: Stream the file to the presigned URL
+        let progress = self.progress.clone();
+        let mut sent = 0;
+        let response = self
+            .put_verified(
+                &presigned_response.upload_url,
+                path,
+                (0, size),
+                checksums,
+                Some(content_type),
+                move |chunk_len| {
+                    if let Some(progress) = &progress {
+                        sent += chunk_len;
+                        progress(sent, size);
+                    }
+                },
+            )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to upload to S3: {}", e))?;
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
+            if is_checksum_rejection(&error_text) {
+                return Err(anyhow::anyhow!(
+                    "Storage rejected {} because its checksum did not match: the upload was corrupted on the way",
+                    filename
+                )
+                .into());
+            }
             return Err(
                 anyhow::anyhow!("S3 upload failed with status {}: {}", status, error_text).into(),
             );
         }
 
+        Ok(presigned_response.s3_key)
+    }
+
+    /// PUT the `(offset, len)` range of `path` with its checksums, checking
+    /// that the bytes streamed still hash to them and, when storage reports
+    /// one, that its checksum agrees. `on_sent` is called with the size of
+    /// every chunk handed to the HTTP client.
+    async fn put_verified(
+        &self,
+        url: &str,
+        path: &Path,
+        (offset, len): (u64, u64),
+        checksums: &Checksums,
+        content_type: Option<&str>,
+        mut on_sent: impl FnMut(u64) + Send + Sync + 'static,
+    ) -> anyhow::Result<reqwest::Response> {
+        let hasher = Arc::new(Mutex::new(ChecksumHasher::default()));
+        let sink = hasher.clone();
+        let stream = upload::file_range_stream(path, offset, len, move |chunk| {
+            sink.lock().unwrap().update(chunk);
+            on_sent(chunk.len() as u64);
+        })
+        .await?;
+
+        let mut request = self
+            .http
+            .put(url)
+            .header("Content-Length", len)
+            .header("Content-MD5", checksums.md5_base64())
+            .header("x-amz-checksum-sha256", checksums.sha256_base64());
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+        let response = request
+            .body(reqwest::Body::wrap_stream(stream))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+
+        let streamed = std::mem::take(&mut *hasher.lock().unwrap()).finish();
+        if &streamed != checksums {
+            anyhow::bail!(
+                "{} changed while it was being uploaded (SHA-256 {} before, {} sent)",
+                path.display(),
+                checksums.sha256_hex(),
+                streamed.sha256_hex()
+            );
+        }
+        let stored = response
+            .headers()
+            .get("x-amz-checksum-sha256")
+            .and_then(|checksum| checksum.to_str().ok());
+        if let Some(stored) = stored {
+            if stored != checksums.sha256_base64() {
+                anyhow::bail!(
+                    "Storage reports SHA-256 {} for {} but {} was sent",
+                    stored,
+                    path.display(),
+                    checksums.sha256_base64()
+                );
+            }
+        }
+        Ok(response)
+    }
+
+    /// Upload a large file in parts, resuming an earlier upload of the same
+    /// content that was interrupted. Returns the file's key.
+    async fn upload_multipart(
+        &self,
+        file: &HashedFile<'_>,
+        app_id: Uuid,
+        version: &str,
+        filename: &str,
+        content_type: &str,
+    ) -> Result<String> {
+        let (path, size, part_checksums) = (file.path, file.size, &file.part_checksums);
+        let sha256 = file.checksums.sha256_hex();
+        let state_path = MultipartState::path(app_id, version, filename, &sha256)?;
+        self.abort_superseded_uploads(app_id, version, filename, &sha256)
+            .await;
+
+        let resumed = MultipartState::load(&state_path);
+        let mut state = match resumed {
+            Some(ref state) => {
+                self.output(&format!(
+                    "♻️  Resuming interrupted upload of {} ({} of {} parts already uploaded)",
+                    filename,
+                    state.completed.len(),
+                    state.part_count()
+                ));
+                state.clone()
+            }
+            None => {
+                MultipartState::start(self, app_id, version, filename, content_type, &sha256, size)
+                    .await?
+            }
+        };
+        state.save(&state_path)?;
+
+        let part_urls = match self.part_urls(&state, part_checksums).await {
+            // The platform no longer knows the upload, e.g. because it expired
+            Err(AetherError::Api { status: 404, .. }) if resumed.is_some() => {
+                self.output("⚠️  Interrupted upload expired, starting over");
+                state = MultipartState::start(
+                    self,
+                    app_id,
+                    version,
+                    filename,
+                    content_type,
+                    &sha256,
+                    size,
+                )
+                .await?;
+                state.save(&state_path)?;
+                self.part_urls(&state, part_checksums).await?
+            }
+            part_urls => part_urls?,
+        };
+
+        let sent = Arc::new(AtomicU64::new(
+            state
+                .completed
+                .keys()
+                .map(|&part_number| state.part_range(part_number).1)
+                .sum(),
+        ));
+        if let Some(progress) = &self.progress {
+            progress(sent.load(Ordering::Relaxed), size);
+        }
+
+        let parts: Vec<_> = part_urls
+            .into_iter()
+            .map(|part| {
+                let range = state.part_range(part.part_number);
+                let checksums = part_checksums[part.part_number as usize - 1].clone();
+                (part, range, checksums)
+            })
+            .collect();
+        let mut uploads = futures::stream::iter(parts)
+            .map(|(part, range, checksums)| {
+                self.upload_part(path, part, range, checksums, size, sent.clone())
+            })
+            .buffer_unordered(PART_CONCURRENCY);
+        while let Some(result) = uploads.next().await {
+            match result {
+                Ok(completed) => {
+                    state.completed.insert(completed.part_number, completed);
+                    state.save(&state_path)?;
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Upload of {} interrupted after {} of {} parts: {}\nRun the command again to resume it",
+                        filename,
+                        state.completed.len(),
+                        state.part_count(),
+                        e
+                    )
+                    .into());
+                }
+            }
+        }
+
+        let completion = self
+            .client
+            .complete_multipart_upload(
+                &state.upload_id,
+                CompleteMultipartUploadRequest {
+                    s3_key: state.s3_key.clone(),
+                    parts: state.completed.values().cloned().collect(),
+                },
+            )
+            .await;
+        match completion {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&state_path);
+                Ok(state.s3_key)
+            }
+            // Rejected parts will not be accepted on a retry either
+            Err(
+                e @ AetherError::Api {
+                    status: 400..=499, ..
+                },
+            ) => {
+                self.abort_upload(&state, &state_path).await;
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Presigned URLs for the parts of `state` not uploaded yet.
+    async fn part_urls(
+        &self,
+        state: &MultipartState,
+        part_checksums: &[Checksums],
+    ) -> Result<Vec<MultipartPartUrl>> {
+        let parts: Vec<_> = (1..=state.part_count())
+            .filter(|part_number| !state.completed.contains_key(part_number))
+            .map(|part_number| {
+                let checksums = &part_checksums[part_number as usize - 1];
+                MultipartPartRequest {
+                    part_number,
+                    checksum_sha256: checksums.sha256_base64(),
+                    content_md5: checksums.md5_base64(),
+                }
+            })
+            .collect();
+        if parts.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.client
+            .get_multipart_part_urls(
+                &state.upload_id,
+                MultipartPartUrlsRequest {
+                    s3_key: state.s3_key.clone(),
+                    parts,
+                },
+            )
+            .await
+    }
+
+    /// PUT one part, retrying transient failures with exponential backoff.
+    /// Bytes sent by failed attempts are taken back off `sent`.
+    async fn upload_part(
+        &self,
+        path: &Path,
+        part: MultipartPartUrl,
+        range: (u64, u64),
+        checksums: Checksums,
+        total: u64,
+        sent: Arc<AtomicU64>,
+    ) -> anyhow::Result<CompletedPart> {
+        let mut attempt = 1;
+        loop {
+            let counted = Arc::new(AtomicU64::new(0));
+            let on_sent = {
+                let (sent, counted) = (sent.clone(), counted.clone());
+                let progress = self.progress.clone();
+                move |chunk_len| {
+                    counted.fetch_add(chunk_len, Ordering::Relaxed);
+                    let now = sent.fetch_add(chunk_len, Ordering::Relaxed) + chunk_len;
+                    if let Some(progress) = &progress {
+                        progress(now, total);
+                    }
+                }
+            };
+
+            let result = self
+                .put_verified(&part.upload_url, path, range, &checksums, None, on_sent)
+                .await;
+            let error = match result {
+                Ok(response) if response.status().is_success() => {
+                    let etag = response
+                        .headers()
+                        .get("ETag")
+                        .and_then(|etag| etag.to_str().ok())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "No ETag returned for part {}; the bucket's CORS rules must expose the ETag header",
+                                part.part_number
+                            )
+                        })?;
+                    return Ok(CompletedPart {
+                        part_number: part.part_number,
+                        etag: etag.to_string(),
+                        checksum_sha256: checksums.sha256_base64(),
+                    });
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let error_text = response.text().await.unwrap_or_default();
+                    let error = anyhow::anyhow!(
+                        "part {} failed with status {}: {}",
+                        part.part_number,
+                        status,
+                        error_text
+                    );
+                    // Bytes corrupted on the way are worth sending again
+                    let transient = status.is_server_error()
+                        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                        || is_checksum_rejection(&error_text);
+                    if !transient {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) => anyhow::anyhow!("part {} failed: {}", part.part_number, e),
+            };
+
+            let failed = counted.load(Ordering::Relaxed);
+            let now = sent.fetch_sub(failed, Ordering::Relaxed) - failed;
+            if let Some(progress) = &self.progress {
+                progress(now, total);
+            }
+            if attempt == PART_ATTEMPTS {
+                return Err(error);
+            }
+            tokio::time::sleep(Duration::from_millis(500 << attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Abort saved uploads that can no longer be resumed: those of an older
+    /// build of the same file and those older than `STALE_UPLOAD_DAYS`.
+    async fn abort_superseded_uploads(
+        &self,
+        app_id: Uuid,
+        version: &str,
+        filename: &str,
+        sha256: &str,
+    ) {
+        let Ok(entries) = Config::uploads_dir().and_then(|dir| Ok(std::fs::read_dir(dir)?)) else {
+            return;
+        };
+        let stale_before = Utc::now() - chrono::Duration::days(STALE_UPLOAD_DAYS);
+        for entry in entries.flatten() {
+            let state_path = entry.path();
+            let Some(state) = MultipartState::load(&state_path) else {
+                continue;
+            };
+            if state.is_superseded_by(app_id, version, filename, sha256)
+                || state.started_at < stale_before
+            {
+                self.abort_upload(&state, &state_path).await;
+            }
+        }
+    }
+
+    async fn abort_upload(&self, state: &MultipartState, state_path: &Path) {
+        // Best effort: the platform also expires abandoned uploads itself
+        let _ = self
+            .client
+            .abort_multipart_upload(
+                &state.upload_id,
+                AbortMultipartUploadRequest {
+                    s3_key: state.s3_key.clone(),
+                },
+            )
+            .await;
+        let _ = std::fs::remove_file(state_path);
+    }
+}
+
+impl ArtifactStore for PresignedUploader {
+    fn describe(&self) -> String {
+        "platform storage".to_string()
+    }
+
+    fn upload_artifact<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        format: ArtifactFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let default_filename = format!("artifact.{}", format.extension());
+            let filename = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(&default_filename);
+            self.upload_file(path, app_id, version, filename, format.content_type(), true)
+                .await
+        })
+    }
+
+    fn find_dependency_layer<'a>(
+        &'a self,
+        app_id: Uuid,
+        layer_key: &'a str,
+        _compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<Option<DependencyLayerRef>>> {
+        Box::pin(self.client.find_dependency_layer(app_id, layer_key))
+    }
+
+    fn upload_dependency_layer<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let filename = format!("deps-{}.{}", layer_key, compression.extension());
+            self.upload_file(
+                path,
+                app_id,
+                version,
+                &filename,
+                compression.content_type(),
+                false,
+            )
+            .await
+        })
+    }
+
+    fn upload_build_log<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(self.upload_file(path, app_id, version, "build.log", "text/plain", false))
+    }
+
+    fn download_url<'a>(
+        &'a self,
+        app_id: Uuid,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ArtifactDownload>>> {
+        Box::pin(
+            self.client
+                .get_presigned_download_url(GeneratePresignedDownloadUrlRequest {
+                    app_id,
+                    s3_key: key.to_string(),
+                }),
+        )
+    }
+
+    fn list_artifacts(&self, app_id: Uuid) -> BoxFuture<'_, Result<Vec<StoredArtifact>>> {
+        Box::pin(self.client.list_artifacts(app_id))
+    }
+
+    fn delete_artifact<'a>(
+        &'a self,
+        app_id: Uuid,
+        artifact: &'a StoredArtifact,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.client.delete_artifact(app_id, &artifact.sha256))
+    }
+}
+
+/// Whether storage rejected an upload because the bytes it received did
+/// not match the checksum headers.
+fn is_checksum_rejection(error_text: &str) -> bool {
+    ["BadDigest", "InvalidDigest", "XAmzContentChecksumMismatch"]
+        .iter()
+        .any(|code| error_text.contains(code))
+}
+
+/// Progress of a multipart upload, saved under `~/.aether/uploads` after
+/// every part so an interrupted upload can pick up where it stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MultipartState {
+    upload_id: String,
+    s3_key: String,
+    app_id: Uuid,
+    version: String,
+    filename: String,
+    /// SHA-256 of the file, so only identical content resumes the upload
+    sha256: String,
+    size: u64,
+    part_size: u64,
+    started_at: DateTime<Utc>,
+    /// Every uploaded part, keyed by part number
+    completed: BTreeMap<u32, CompletedPart>,
+}
+
+impl MultipartState {
+    async fn start(
+        uploader: &PresignedUploader,
+        app_id: Uuid,
+        version: &str,
+        filename: &str,
+        content_type: &str,
+        sha256: &str,
+        size: u64,
+    ) -> Result<Self> {
+        let part_size = part_size_for(size);
+        let upload = uploader
+            .client
+            .create_multipart_upload(CreateMultipartUploadRequest {
+                app_id,
+                version: version.to_string(),
+                filename: filename.to_string(),
+                content_type: content_type.to_string(),
+                size,
+                part_count: size.div_ceil(part_size) as u32,
+                sha256: sha256.to_string(),
+            })
+            .await?;
+        Ok(Self {
+            upload_id: upload.upload_id,
+            s3_key: upload.s3_key,
+            app_id,
+            version: version.to_string(),
+            filename: filename.to_string(),
+            sha256: sha256.to_string(),
+            size,
+            part_size,
+            started_at: Utc::now(),
+            completed: BTreeMap::new(),
+        })
+    }
+
+    /// Whether this is an upload of an older build of the file, which can
+    /// never be resumed.
+    fn is_superseded_by(&self, app_id: Uuid, version: &str, filename: &str, sha256: &str) -> bool {
+        self.app_id == app_id
+            && self.version == version
+            && self.filename == filename
+            && self.sha256 != sha256
+    }
+
+    fn path(app_id: Uuid, version: &str, filename: &str, sha256: &str) -> Result<PathBuf> {
+        let key = Sha256::digest(format!("{}/{}/{}/{}", app_id, version, filename, sha256));
+        Ok(Config::uploads_dir()?.join(format!("{:x}.json", key)))
+    }
+
+    fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write then rename, so an interruption never leaves a torn file
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn part_count(&self) -> u32 {
+        self.size.div_ceil(self.part_size) as u32
+    }
+
+    /// Offset and length of a part, numbered from 1.
+    fn part_range(&self, part_number: u32) -> (u64, u64) {
+        let offset = u64::from(part_number - 1) * self.part_size;
+        (offset, self.part_size.min(self.size - offset))
+    }
+}
+
+/// Part size that keeps a `size` byte upload within `MAX_PARTS` parts.
+fn part_size_for(size: u64) -> u64 {
+    MIN_PART_SIZE.max(size.div_ceil(MAX_PARTS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_ranges() {
+        let state = MultipartState {
+            upload_id: "upload".to_string(),
+            s3_key: "artifacts/app.tar.gz".to_string(),
+            app_id: Uuid::nil(),
+            version: "1.0.0".to_string(),
+            filename: "app.tar.gz".to_string(),
+            sha256: String::new(),
+            size: 2 * MIN_PART_SIZE + 10,
+            part_size: part_size_for(2 * MIN_PART_SIZE + 10),
+            started_at: Utc::now(),
+            completed: BTreeMap::new(),
+        };
+        assert_eq!(state.part_count(), 3);
+        assert_eq!(state.part_range(1), (0, MIN_PART_SIZE));
+        assert_eq!(state.part_range(3), (2 * MIN_PART_SIZE, 10));
 
-        // Step 3: Generate download presigned URL
-        // For now, we'll use a placeholder URL - this should be generated by backend
-        let download_url = format!("Download URL for: {}", presigned_response.s3_key);
+        // Huge files grow the parts instead of exceeding the part limit
+        let size: u64 = 200 * 1024 * 1024 * 1024;
+        assert!(size.div_ceil(part_size_for(size)) <= MAX_PARTS);
+    }
+
+    #[tokio::test]
+    async fn test_rebuilt_artifacts_resume_saved_uploads() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("index.html"), "<h1>Hello</h1>").unwrap();
+        let build = |run: &'static str| {
+            let (dir, out) = (dir.path().to_path_buf(), out.path().join(run));
+            async move {
+                std::fs::create_dir_all(&out).unwrap();
+                let builder = crate::builder::ProjectBuilder::new(&dir)
+                    .unwrap()
+                    .with_build_log(out.join("build.log"))
+                    .with_output_callback(|_| {});
+                let version = builder.get_version();
+                let artifact = builder.build(None).await.unwrap();
+                let filename = artifact.file_name().unwrap().to_string_lossy().into_owned();
+                let sha256 = crate::utils::sha256_file(&artifact).unwrap();
+                std::fs::remove_file(&artifact).unwrap();
+                (version, filename, sha256)
+            }
+        };
+
+        // An upload of the first build, interrupted
+        let (version, filename, sha256) = build("first").await;
+        let state = MultipartState {
+            upload_id: "upload".to_string(),
+            s3_key: "artifacts/app.tar.gz".to_string(),
+            app_id: Uuid::nil(),
+            version,
+            filename,
+            sha256,
+            size: MULTIPART_THRESHOLD,
+            part_size: part_size_for(MULTIPART_THRESHOLD),
+            started_at: Utc::now(),
+            completed: BTreeMap::new(),
+        };
+        let saved_at =
+            MultipartState::path(Uuid::nil(), &state.version, &state.filename, &state.sha256)
+                .unwrap();
+
+        // Rerunning the deploy rebuilds the same artifact and finds the upload
+        let (version, filename, sha256) = build("second").await;
+        assert!(!state.is_superseded_by(Uuid::nil(), &version, &filename, &sha256));
+        assert_eq!(
+            MultipartState::path(Uuid::nil(), &version, &filename, &sha256).unwrap(),
+            saved_at
+        );
+
+        // Changed sources make the saved upload useless
+        std::fs::write(dir.path().join("index.html"), "<h1>Changed</h1>").unwrap();
+        let (version, filename, sha256) = build("third").await;
+        assert!(state.is_superseded_by(Uuid::nil(), &version, &filename, &sha256));
+    }
+
+    /// Answer one request with each of `responses` (status and JSON body),
+    /// returning the platform URL and the requests received.
+    async fn serve_platform(
+        responses: Vec<(u16, String)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let read = socket.read(&mut request).await.unwrap();
+                requests.push(String::from_utf8_lossy(&request[..read]).into_owned());
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_artifacts_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.tar.gz");
+        std::fs::write(&path, b"artifact").unwrap();
+        let sha256 = format!("{:x}", Sha256::digest(b"artifact"));
+
+        // Only the lookup is answered; an upload would fail to connect
+        let stored = format!(
+            r#"{{"artifact_url":"artifacts/app/1.0.0/app.tar.gz","sha256":"{}","size":8}}"#,
+            sha256
+        );
+        let (url, server) = serve_platform(vec![(200, stored)]).await;
+        let store = PresignedUploader::new(ApiClient::new(url, None).unwrap());
+        let format = ArtifactFormat {
+            artifact_type: crate::api::ArtifactType::Tarball,
+            compression: CompressionFormat::Gzip,
+        };
+        let uploaded = store
+            .upload_artifact(&path, Uuid::nil(), "1.0.1", format)
+            .await
+            .unwrap();
+
+        assert!(uploaded.reused);
+        assert_eq!(uploaded.key, "artifacts/app/1.0.0/app.tar.gz");
+        assert_eq!(uploaded.sha256, sha256);
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with(&format!(
+            "GET /api/v1/apps/{}/artifacts/{} ",
+            Uuid::nil(),
+            sha256
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_dependency_layers_are_looked_up_on_the_platform() {
+        let (url, server) = serve_platform(vec![
+            (404, String::new()),
+            (
+                200,
+                r#"{"key":"abc123","artifact_url":"s3://platform/deps.tar.gz"}"#.to_string(),
+            ),
+        ])
+        .await;
+
+        let store = PresignedUploader::new(ApiClient::new(url, None).unwrap());
+        let gzip = CompressionFormat::Gzip;
+        assert!(store
+            .find_dependency_layer(Uuid::nil(), "abc123", gzip)
+            .await
+            .unwrap()
+            .is_none());
+        let found = store
+            .find_dependency_layer(Uuid::nil(), "abc123", gzip)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.artifact_url, "s3://platform/deps.tar.gz");
 
-        Ok((presigned_response.s3_key, download_url))
+        let requests = server.await.unwrap();
+        assert!(
+            requests[0].starts_with(&format!("GET /api/v1/apps/{}/layers/abc123 ", Uuid::nil()))
+        );
     }
 }
@@ -0,0 +1,154 @@
+//! Preview deployments: throwaway copies of an application, one per git
+//! branch, linked to the application they preview.
+
+use crate::manifest;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Longest application name the platform accepts.
+const MAX_NAME_LEN: usize = 63;
+
+/// Name of the preview application of `app` for `branch`, e.g.
+/// `shop-preview-feature-login` for `feature/login`.
+pub fn preview_name(app: &str, branch: &str) -> String {
+    let mut slug = String::new();
+    for c in branch.to_lowercase().chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    let name = format!("{}-preview-{}", app, slug);
+    if !slug.is_empty() && name.len() <= MAX_NAME_LEN {
+        return name;
+    }
+
+    // Shorten the name, keeping it unique per branch with a hash of the
+    // branch name
+    let hash = format!("{:x}", Sha256::digest(branch.as_bytes()));
+    let suffix = &hash[..8];
+    let mut name = name;
+    name.truncate(MAX_NAME_LEN - suffix.len() - 1);
+    format!("{}-{}", name.trim_end_matches('-'), suffix)
+}
+
+/// The branch checked out in the repository containing `path`, or `None`
+/// outside a repository or on a detached checkout.
+pub fn current_branch(path: &Path) -> Option<String> {
+    manifest::git(path, &["rev-parse", "--abbrev-ref", "HEAD"]).filter(|branch| branch != "HEAD")
+}
+
+/// Branches that still exist for the repository containing `path`: its
+/// local branches and those its remotes have right now, asked with `git
+/// ls-remote` since remote-tracking branches can be stale. `None` outside a
+/// repository or when a remote cannot be reached.
+pub fn live_branches(path: &Path) -> Option<HashSet<String>> {
+    let heads = manifest::git(path, &["for-each-ref", "--format=%(refname)", "refs/heads"])?;
+    let mut branches = parse_branches(&heads);
+    for remote in manifest::git(path, &["remote"])?.lines() {
+        branches.extend(parse_branches(&remote_heads(path, remote)?));
+    }
+    Some(branches)
+}
+
+/// `git ls-remote --heads` output for `remote`, failing rather than
+/// prompting for credentials.
+fn remote_heads(path: &Path, remote: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--heads", remote])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .current_dir(path)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Branch names in `git for-each-ref` or `git ls-remote` output, which puts
+/// the commit before each ref.
+fn parse_branches(refs: &str) -> HashSet<String> {
+    refs.lines()
+        .filter_map(|line| line.split_whitespace().last()?.strip_prefix("refs/heads/"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_name() {
+        assert_eq!(
+            preview_name("shop", "feature/Login_Form"),
+            "shop-preview-feature-login-form"
+        );
+        assert_eq!(preview_name("shop", "main"), "shop-preview-main");
+
+        let long = preview_name("shop", &"very-long-branch-name-".repeat(4));
+        assert!(long.len() <= MAX_NAME_LEN);
+        assert!(long.starts_with("shop-preview-very-long-branch"));
+        assert_ne!(
+            long,
+            preview_name("shop", &"very-long-branch-name-".repeat(5))
+        );
+
+        let unnamed = preview_name("shop", "___");
+        assert!(unnamed.starts_with("shop-preview-"));
+        crate::utils::validate_app_name(&unnamed).unwrap();
+    }
+
+    #[test]
+    fn test_parse_branches() {
+        let branches = parse_branches(
+            "refs/heads/main\nrefs/heads/feature/login\n0f3c2a9d\trefs/heads/fix-typo\n0f3c2a9d\trefs/tags/v1\n",
+        );
+        let mut branches: Vec<_> = branches.into_iter().collect();
+        branches.sort();
+        assert_eq!(branches, ["feature/login", "fix-typo", "main"]);
+    }
+
+    #[test]
+    fn test_live_branches_ignore_stale_remote_tracking_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |repo: &str, args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir.path().join(repo))
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        std::fs::create_dir(dir.path().join("origin")).unwrap();
+        git("origin", &["init", "-b", "main"]);
+        git("origin", &["commit", "--allow-empty", "-m", "init"]);
+        git("origin", &["branch", "feature/login"]);
+        git("origin", &["branch", "fix-typo"]);
+        git(".", &["clone", "origin", "clone"]);
+        git("clone", &["branch", "local-only"]);
+
+        // Deleted on the remote after the clone fetched it
+        git("origin", &["branch", "-D", "fix-typo"]);
+
+        let clone = dir.path().join("clone");
+        let mut branches: Vec<_> = live_branches(&clone).unwrap().into_iter().collect();
+        branches.sort();
+        assert_eq!(branches, ["feature/login", "local-only", "main"]);
+
+        // Nothing counts as gone while a remote cannot be reached
+        git("clone", &["remote", "add", "offline", "/nonexistent/repo"]);
+        assert!(live_branches(&clone).is_none());
+    }
+}
@@ -0,0 +1,70 @@
+use crate::api::{CacheRule, RedirectRule};
+use crate::{AetherError, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Per-project settings read from `aether.json` in the project root.
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub build: BuildConfig,
+    /// Command that starts the application, replacing the detected one
+    pub start: Option<String>,
+    /// Deploy the project as a static site
+    #[serde(rename = "static")]
+    pub static_site: Option<StaticConfig>,
+    /// Health checks a deployment must pass, or be rolled back
+    pub health_check: Option<HealthCheckConfig>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default)]
+pub struct BuildConfig {
+    /// Shell commands run before the build step
+    pub pre_build: Vec<String>,
+    /// Shell command replacing the package.json build script
+    pub command: Option<String>,
+    /// Shell commands run after the build step
+    pub post_build: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Clone)]
+#[serde(default)]
+pub struct StaticConfig {
+    /// Build output directory to deploy, relative to the project root
+    pub output_dir: Option<String>,
+    /// Serve `index.html` for paths matching no file
+    pub spa: Option<bool>,
+    /// Cache-Control rules, replacing the framework defaults
+    pub cache: Option<Vec<CacheRule>>,
+    pub redirects: Vec<RedirectRule>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct HealthCheckConfig {
+    /// Path requested on the deployment URL, e.g. `/healthz`
+    pub path: String,
+    /// Successful checks in a row that make the deployment healthy
+    pub successes: Option<u32>,
+    /// Time between checks, e.g. `5s`
+    pub interval: Option<String>,
+    /// Time the deployment has to become healthy, e.g. `2m`
+    pub timeout: Option<String>,
+}
+
+impl ProjectConfig {
+    pub const FILE_NAME: &'static str = "aether.json";
+
+    /// Load the project config, falling back to defaults when the project
+    /// has no `aether.json`.
+    pub fn load(project_path: &Path) -> Result<Self> {
+        let path = project_path.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| AetherError::config(format!("Invalid {}: {}", Self::FILE_NAME, e)))
+    }
+}
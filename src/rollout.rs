@@ -0,0 +1,267 @@
+//! Canary and blue-green rollouts, promoted step by step by the CLI.
+
+use crate::api::{ApiClient, Deployment, Rollout, RolloutState, RolloutStrategy};
+use crate::{utils, AetherError, Result};
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
+
+/// Traffic percentages of a canary deployment without `--steps`.
+pub const DEFAULT_CANARY_STEPS: &[u8] = &[10, 50, 100];
+
+/// Wait between canary steps without `--interval`.
+pub const DEFAULT_CANARY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Check that canary `steps` are increasing percentages ending at 100.
+pub fn validate_canary_steps(steps: &[u8]) -> Result<()> {
+    if steps.last() != Some(&100) {
+        return Err(AetherError::config(
+            "Canary steps must end at 100, e.g. --steps 10,50,100",
+        ));
+    }
+    if steps.first() == Some(&0) || steps.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(AetherError::config(format!(
+            "Canary steps must be increasing percentages from 1 to 100, got {}",
+            steps
+                .iter()
+                .map(|step| step.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        )));
+    }
+    Ok(())
+}
+
+/// One line describing where `rollout` stands.
+pub fn describe(rollout: &Rollout) -> String {
+    match (rollout.state, rollout.strategy) {
+        (RolloutState::Promoted, _) => "✅ New version serves all traffic".to_string(),
+        (RolloutState::Aborted, _) => {
+            "⏪ Aborted, traffic is back on the previous deployment".to_string()
+        }
+        (RolloutState::InProgress, RolloutStrategy::BlueGreen) => {
+            "🔵 New version is running alongside the current one, without traffic".to_string()
+        }
+        (RolloutState::InProgress, _) => {
+            format!("🐤 Canary serves {}% of traffic", rollout.traffic_percent)
+        }
+    }
+}
+
+/// Deployments of one application that may still serve traffic: those
+/// starting or running, the newest one, any with a rollout in progress, and
+/// the deployment each such rollout moves traffic away from.
+pub fn live_deployments(deployments: &[Deployment]) -> Vec<&Deployment> {
+    let newest = deployments
+        .iter()
+        .max_by_key(|deployment| deployment.created_at);
+    let mut live: Vec<&Deployment> = deployments
+        .iter()
+        .filter(|deployment| {
+            deployment.is_starting()
+                || deployment.is_running()
+                || newest.is_some_and(|newest| newest.id == deployment.id)
+        })
+        .collect();
+    for deployment in deployments
+        .iter()
+        .filter(|deployment| is_rolling_out(deployment))
+    {
+        live.push(deployment);
+        live.extend(previous(deployments, deployment));
+    }
+    // Ties are ordered by ID so that duplicates end up next to each other
+    live.sort_by_key(|deployment| (std::cmp::Reverse(deployment.created_at), deployment.id));
+    live.dedup_by_key(|deployment| deployment.id);
+    live
+}
+
+/// The deployment `deployment` replaced: the latest earlier one whose own
+/// rollout was not aborted.
+fn previous<'a>(deployments: &'a [Deployment], deployment: &Deployment) -> Option<&'a Deployment> {
+    earlier(deployments, deployment).max_by_key(|previous| previous.created_at)
+}
+
+/// The deployment to roll `deployment` back to: the latest earlier one that
+/// ran successfully.
+pub fn rollback_target<'a>(
+    deployments: &'a [Deployment],
+    deployment: &Deployment,
+) -> Option<&'a Deployment> {
+    earlier(deployments, deployment)
+        .filter(|previous| previous.has_run())
+        .max_by_key(|previous| previous.created_at)
+}
+
+/// Deployments created before `deployment` whose own rollout was not
+/// aborted.
+fn earlier<'a, 'b>(
+    deployments: &'a [Deployment],
+    deployment: &'b Deployment,
+) -> impl Iterator<Item = &'a Deployment> + 'b
+where
+    'a: 'b,
+{
+    deployments.iter().filter(move |previous| {
+        previous.id != deployment.id
+            && previous.created_at <= deployment.created_at
+            && !previous
+                .rollout
+                .as_ref()
+                .is_some_and(|rollout| rollout.state == RolloutState::Aborted)
+    })
+}
+
+fn is_rolling_out(deployment: &Deployment) -> bool {
+    deployment
+        .rollout
+        .as_ref()
+        .is_some_and(|rollout| rollout.state == RolloutState::InProgress)
+}
+
+/// Promote `deployment` of `app` one step at a time, waiting `interval`
+/// before each step, until it serves all traffic. Stops early when the
+/// rollout is promoted or aborted elsewhere, e.g. with `aether abort`.
+pub async fn drive(
+    client: &ApiClient,
+    app: &str,
+    mut deployment: Deployment,
+    interval: Duration,
+) -> Result<Deployment> {
+    while let Some(rollout) = deployment
+        .rollout
+        .as_ref()
+        .filter(|rollout| rollout.state == RolloutState::InProgress)
+    {
+        let next = rollout.next_percent();
+        if !interval.is_zero() {
+            wait(interval, next, app).await;
+            deployment = client
+                .get_deployment(deployment.app_id, deployment.id)
+                .await?;
+            if !deployment
+                .rollout
+                .as_ref()
+                .is_some_and(|rollout| rollout.state == RolloutState::InProgress)
+            {
+                if let Some(rollout) = &deployment.rollout {
+                    println!("{}", describe(rollout));
+                }
+                break;
+            }
+        }
+
+        deployment = client
+            .promote_deployment(deployment.app_id, deployment.id)
+            .await?;
+        if let Some(rollout) = &deployment.rollout {
+            println!("{}", describe(rollout));
+        }
+    }
+    Ok(deployment)
+}
+
+/// Count down `interval` before promoting to `next` percent of traffic.
+async fn wait(interval: Duration, next: u8, app: &str) {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    let deadline = tokio::time::Instant::now() + interval;
+    loop {
+        let left = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        pb.set_message(format!(
+            "Moving to {}% of traffic in {} {}",
+            next,
+            utils::format_duration(left.as_secs().max(1)),
+            style(format!("(aether abort {} to stop)", app)).dim()
+        ));
+        pb.tick();
+        tokio::time::sleep(left.min(Duration::from_secs(1))).await;
+    }
+    pb.finish_and_clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn deployment(day: u32, status: &str, rollout: Option<RolloutState>) -> Deployment {
+        Deployment {
+            id: uuid::Uuid::new_v4(),
+            app_id: uuid::Uuid::nil(),
+            version: format!("1.0.{}", day),
+            status: status.to_string(),
+            artifact_url: Some(format!("s3://artifacts/{}.tar.gz", day)),
+            created_at: chrono::Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap(),
+            rollout: rollout.map(|state| Rollout {
+                strategy: RolloutStrategy::Canary,
+                traffic_percent: 10,
+                steps: vec![10, 50, 100],
+                state,
+            }),
+        }
+    }
+
+    fn versions(deployments: Vec<&Deployment>) -> Vec<&str> {
+        deployments
+            .into_iter()
+            .map(|deployment| deployment.version.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_live_deployments() {
+        // A canary of 1.0.4 is splitting traffic with 1.0.2, since the
+        // canary of 1.0.3 was aborted, while 1.0.5 is still starting
+        let deployments = vec![
+            deployment(1, "stopped", None),
+            deployment(2, "stopped", None),
+            deployment(3, "failed", Some(RolloutState::Aborted)),
+            deployment(4, "running", Some(RolloutState::InProgress)),
+            deployment(5, "Deploying", None),
+        ];
+        assert_eq!(
+            versions(live_deployments(&deployments)),
+            ["1.0.5", "1.0.4", "1.0.2"]
+        );
+
+        // Once promoted, only the newest deployment serves traffic
+        let deployments = vec![
+            deployment(1, "stopped", None),
+            deployment(2, "failed", Some(RolloutState::Promoted)),
+        ];
+        assert_eq!(versions(live_deployments(&deployments)), ["1.0.2"]);
+        assert!(live_deployments(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_rollback_target_ran_successfully() {
+        let deployments = vec![
+            deployment(1, "superseded", None),
+            deployment(2, "running", Some(RolloutState::Aborted)),
+            deployment(3, "failed", None),
+            deployment(4, "pending", None),
+            deployment(5, "running", None),
+        ];
+        let target = rollback_target(&deployments, &deployments[4]).unwrap();
+        assert_eq!(target.version, "1.0.1");
+        assert!(rollback_target(&deployments, &deployments[0]).is_none());
+    }
+
+    #[test]
+    fn test_validate_canary_steps() {
+        assert!(validate_canary_steps(&[10, 50, 100]).is_ok());
+        assert!(validate_canary_steps(&[100]).is_ok());
+        assert!(validate_canary_steps(&[10, 50]).is_err());
+        assert!(validate_canary_steps(&[50, 10, 100]).is_err());
+        assert!(validate_canary_steps(&[0, 100]).is_err());
+        assert!(validate_canary_steps(&[]).is_err());
+    }
+}
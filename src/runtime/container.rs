@@ -0,0 +1,306 @@
+use super::{directory_name, PackageDir, Runtime};
+use crate::api::ArtifactType;
+use crate::builder::{ProjectBuilder, StagingDir};
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// File name of the OCI archive inside the build's staging directory.
+const IMAGE_ARCHIVE: &str = "image.oci.tar";
+
+/// Tools able to build a Dockerfile into an OCI archive, in order of
+/// preference. Daemonless builders come first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageTool {
+    Buildah,
+    Podman,
+    DockerBuildx,
+}
+
+impl ImageTool {
+    /// The first image builder installed on this machine.
+    pub fn detect() -> Result<Self> {
+        let probes: [(Self, &str, &[&str]); 3] = [
+            (ImageTool::Buildah, "buildah", &["--version"]),
+            (ImageTool::Podman, "podman", &["--version"]),
+            (ImageTool::DockerBuildx, "docker", &["buildx", "version"]),
+        ];
+
+        probes
+            .into_iter()
+            .find(|(_, program, args)| {
+                std::process::Command::new(program)
+                    .args(*args)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|status| status.success())
+            })
+            .map(|(tool, _, _)| tool)
+            .ok_or_else(|| {
+                AetherError::build(
+                    "No image builder found; install buildah, podman or docker buildx to deploy with --dockerfile",
+                )
+            })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageTool::Buildah => "buildah",
+            ImageTool::Podman => "podman",
+            ImageTool::DockerBuildx => "docker buildx",
+        }
+    }
+
+    /// Commands that build the image and export it to `output` as an OCI
+    /// archive.
+    fn commands(self, context: &Path, dockerfile: &Path, tag: &str, output: &Path) -> Vec<Command> {
+        let build = |program: &str, prefix: &[&str]| {
+            let mut cmd = Command::new(program);
+            cmd.current_dir(context);
+            cmd.args(prefix);
+            cmd.arg("-f").arg(dockerfile).args(["-t", tag]);
+            cmd
+        };
+
+        match self {
+            ImageTool::Buildah => {
+                let mut build = build("buildah", &["build", "--format", "oci"]);
+                build.arg(".");
+                let mut push = Command::new("buildah");
+                push.args(["push", tag])
+                    .arg(format!("oci-archive:{}:{}", output.display(), tag));
+                vec![build, push]
+            }
+            ImageTool::Podman => {
+                let mut build = build("podman", &["build", "--format", "oci"]);
+                build.arg(".");
+                let mut save = Command::new("podman");
+                save.args(["save", "--format", "oci-archive", "-o"])
+                    .arg(output)
+                    .arg(tag);
+                vec![build, save]
+            }
+            ImageTool::DockerBuildx => {
+                let mut build = build("docker", &["buildx", "build"]);
+                build
+                    .arg("--output")
+                    .arg(format!("type=oci,dest={}", output.display()))
+                    .arg(".");
+                vec![build]
+            }
+        }
+    }
+}
+
+/// Services deployed as an OCI image built from a Dockerfile instead of a
+/// tarball for one of the language runtimes.
+pub struct ContainerRuntime {
+    project_path: PathBuf,
+    /// Dockerfile location relative to the build context
+    dockerfile: PathBuf,
+    name: String,
+    version: Option<String>,
+    tool: ImageTool,
+}
+
+impl ContainerRuntime {
+    pub fn new(project_path: &Path, dockerfile: &Path) -> Result<Self> {
+        let dockerfile_path = project_path.join(dockerfile);
+        if !dockerfile_path.is_file() {
+            return Err(AetherError::invalid_project(format!(
+                "Dockerfile not found: {}",
+                dockerfile_path.display()
+            )));
+        }
+        let dockerfile = dockerfile_path
+            .strip_prefix(project_path)
+            .map(Path::to_path_buf)
+            .unwrap_or(dockerfile_path);
+
+        // Name and version come from the project's own manifest if it has one
+        let detected = super::detect(project_path).ok();
+        let name = detected
+            .as_ref()
+            .map(|runtime| runtime.app_name().to_string())
+            .unwrap_or_else(|| directory_name(project_path));
+        let version = detected
+            .as_ref()
+            .and_then(|runtime| runtime.version().map(str::to_string));
+
+        Ok(Self {
+            project_path: project_path.to_path_buf(),
+            dockerfile,
+            name,
+            version,
+            tool: ImageTool::detect()?,
+        })
+    }
+
+    pub fn tool(&self) -> ImageTool {
+        self.tool
+    }
+
+    /// Local tag of the built image, e.g. `aether/api:1.2.0`.
+    pub fn image_tag(&self) -> String {
+        let repository: String = self
+            .name
+            .to_ascii_lowercase()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        format!(
+            "aether/{}:{}",
+            repository.trim_matches('-'),
+            self.version.as_deref().unwrap_or("latest")
+        )
+    }
+}
+
+impl Runtime for ContainerRuntime {
+    fn display_name(&self) -> &'static str {
+        "Container"
+    }
+
+    fn app_name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn runtime_id(&self) -> String {
+        "container".to_string()
+    }
+
+    fn start_command(&self) -> Option<String> {
+        // The image's CMD/ENTRYPOINT starts the service
+        None
+    }
+
+    fn source_root(&self) -> &Path {
+        &self.project_path
+    }
+
+    fn artifact_type(&self) -> ArtifactType {
+        ArtifactType::OciImage
+    }
+
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>> {
+        Box::pin(async move {
+            let tag = self.image_tag();
+            builder.output(&format!(
+                "🐳 Building image {} with {}...",
+                tag,
+                self.tool.name()
+            ));
+
+            let output = StagingDir::create(&self.name)?;
+            let archive = output.path().join(IMAGE_ARCHIVE);
+            let dockerfile = root.join(&self.dockerfile);
+            for cmd in self.tool.commands(root, &dockerfile, &tag, &archive) {
+                builder
+                    .run_command(cmd, "Building image...", "Image build failed")
+                    .await?;
+            }
+
+            if !archive.is_file() {
+                return Err(AetherError::build(format!(
+                    "{} did not produce an OCI archive",
+                    self.tool.name()
+                )));
+            }
+            builder.output("✅ Image built successfully");
+            Ok(PackageDir::archive(output, IMAGE_ARCHIVE))
+        })
+    }
+
+    fn artifact_paths(&self, _package_dir: &Path) -> Result<Vec<PathBuf>> {
+        // The OCI archive is the artifact; nothing is packaged
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(name: &str, version: Option<&str>) -> ContainerRuntime {
+        ContainerRuntime {
+            project_path: PathBuf::from("/srv/api"),
+            dockerfile: PathBuf::from("Dockerfile"),
+            name: name.to_string(),
+            version: version.map(str::to_string),
+            tool: ImageTool::Buildah,
+        }
+    }
+
+    fn command_lines(commands: &[Command]) -> Vec<String> {
+        commands
+            .iter()
+            .map(|cmd| {
+                std::iter::once(cmd.as_std().get_program())
+                    .chain(cmd.as_std().get_args())
+                    .map(|arg| arg.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_image_tag() {
+        assert_eq!(
+            runtime("Billing_API", Some("1.2.0")).image_tag(),
+            "aether/billing_api:1.2.0"
+        );
+        assert_eq!(
+            runtime("@acme/Web.App", None).image_tag(),
+            "aether/acme-web.app:latest"
+        );
+    }
+
+    #[test]
+    fn test_image_tool_commands() {
+        let commands = |tool: ImageTool| {
+            command_lines(&tool.commands(
+                Path::new("/srv/api"),
+                Path::new("docker/api.Dockerfile"),
+                "aether/api:1.0.0",
+                Path::new("/tmp/build/image.oci.tar"),
+            ))
+        };
+
+        assert_eq!(
+            commands(ImageTool::Buildah),
+            [
+                "buildah build --format oci -f docker/api.Dockerfile -t aether/api:1.0.0 .",
+                "buildah push aether/api:1.0.0 oci-archive:/tmp/build/image.oci.tar:aether/api:1.0.0",
+            ]
+        );
+        assert_eq!(
+            commands(ImageTool::Podman),
+            [
+                "podman build --format oci -f docker/api.Dockerfile -t aether/api:1.0.0 .",
+                "podman save --format oci-archive -o /tmp/build/image.oci.tar aether/api:1.0.0",
+            ]
+        );
+        assert_eq!(
+            commands(ImageTool::DockerBuildx),
+            ["docker buildx build -f docker/api.Dockerfile -t aether/api:1.0.0 --output type=oci,dest=/tmp/build/image.oci.tar ."]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use super::{directory_name, list_entries, PackageDir, Runtime};
+use crate::builder::ProjectBuilder;
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// `DENO_DIR` the dependencies are cached into. The platform points
+/// `DENO_DIR` at it when starting the application.
+pub const CACHE_DIR: &str = ".deno";
+
+const DEFAULT_DENO_VERSION: &str = "2";
+
+const CONFIG_FILES: &[&str] = &["deno.json", "deno.jsonc"];
+
+/// Entry files tried when deno.json declares none.
+const ENTRYPOINTS: &[&str] = &["main.ts", "main.js", "mod.ts", "server.ts", "app.ts"];
+
+const ARTIFACT_EXCLUDES: &[&str] = &[".git", ".aether", ".env"];
+
+#[derive(Debug, Default, Deserialize)]
+struct DenoConfig {
+    name: Option<String>,
+    version: Option<String>,
+    /// Task commands, either strings or `{ "command": ... }` objects
+    #[serde(default)]
+    tasks: BTreeMap<String, serde_json::Value>,
+    exports: Option<serde_json::Value>,
+}
+
+impl DenoConfig {
+    fn task(&self, name: &str) -> Option<&str> {
+        let task = self.tasks.get(name)?;
+        task.as_str()
+            .or_else(|| task.get("command").and_then(|command| command.as_str()))
+    }
+}
+
+/// Deno applications configured with `deno.json` or `deno.jsonc`.
+pub struct DenoRuntime {
+    project_path: PathBuf,
+    config: DenoConfig,
+    name: String,
+    deno_version: String,
+    entrypoint: Option<String>,
+}
+
+impl DenoRuntime {
+    pub fn detect(project_path: &Path) -> Result<Option<Self>> {
+        let Some(config_path) = CONFIG_FILES
+            .iter()
+            .map(|file| project_path.join(file))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        let content = std::fs::read_to_string(&config_path)?;
+        let config: DenoConfig = serde_json::from_str(&strip_jsonc(&content)).map_err(|e| {
+            AetherError::invalid_project(format!("Invalid {}: {}", config_path.display(), e))
+        })?;
+
+        let name = config
+            .name
+            .as_deref()
+            // JSR package names are scoped, e.g. @scope/app
+            .map(|name| name.rsplit('/').next().unwrap_or(name).to_string())
+            .unwrap_or_else(|| directory_name(project_path));
+        let entrypoint = resolve_entrypoint(project_path, &config);
+        let deno_version = [".dvmrc", ".deno-version"]
+            .iter()
+            .filter_map(|file| std::fs::read_to_string(project_path.join(file)).ok())
+            .find_map(|version| major_version(&version))
+            .unwrap_or_else(|| DEFAULT_DENO_VERSION.to_string());
+
+        Ok(Some(Self {
+            project_path: project_path.to_path_buf(),
+            config,
+            name,
+            deno_version,
+            entrypoint,
+        }))
+    }
+
+    /// The module the application starts from, relative to the project root.
+    pub fn entrypoint(&self) -> Option<&str> {
+        self.entrypoint.as_deref()
+    }
+
+    fn deno_command(&self, work_dir: &Path) -> Command {
+        let mut cmd = Command::new("deno");
+        cmd.current_dir(work_dir);
+        cmd.env("DENO_DIR", work_dir.join(CACHE_DIR));
+        cmd
+    }
+
+    /// Download every dependency of the entrypoint into [`CACHE_DIR`] so the
+    /// artifact runs without network access.
+    async fn cache_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        root: &Path,
+        entrypoint: &str,
+    ) -> Result<()> {
+        builder.output(&format!("📦 Caching dependencies into {}...", CACHE_DIR));
+
+        let mut cmd = self.deno_command(root);
+        cmd.arg("cache");
+        if builder.is_clean() && root.join("deno.lock").is_file() {
+            cmd.arg("--frozen");
+        }
+        cmd.arg(entrypoint);
+
+        builder
+            .run_command(
+                cmd,
+                "Caching dependencies...",
+                "Failed to cache dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dependencies cached successfully");
+        Ok(())
+    }
+}
+
+impl Runtime for DenoRuntime {
+    fn display_name(&self) -> &'static str {
+        "Deno"
+    }
+
+    fn app_name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.config.version.as_deref()
+    }
+
+    fn runtime_id(&self) -> String {
+        format!("deno:{}", self.deno_version)
+    }
+
+    fn start_command(&self) -> Option<String> {
+        if self.config.task("start").is_some() {
+            return Some("deno task start".to_string());
+        }
+        self.entrypoint.as_ref().map(|entrypoint| {
+            format!(
+                "deno run --cached-only --allow-net --allow-env --allow-read {}",
+                entrypoint
+            )
+        })
+    }
+
+    fn entry_file(&self) -> Option<String> {
+        self.entrypoint.clone()
+    }
+
+    fn source_root(&self) -> &Path {
+        &self.project_path
+    }
+
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>> {
+        Box::pin(async move {
+            match &self.entrypoint {
+                Some(entrypoint) if !root.join(entrypoint).is_file() => {
+                    return Err(AetherError::build(format!(
+                        "Deno entrypoint '{}' not found",
+                        entrypoint
+                    )));
+                }
+                Some(entrypoint) => self.cache_dependencies(builder, root, entrypoint).await?,
+                None => builder.output("⚠️  No entrypoint found, skipping dependency cache"),
+            }
+
+            let build_task = self.config.task("build").map(|_| {
+                let mut cmd = self.deno_command(root);
+                cmd.args(["task", "build"]);
+                ("build", cmd)
+            });
+            builder.run_build_steps(root, build_task).await?;
+
+            Ok(PackageDir::new(root))
+        })
+    }
+
+    fn artifact_paths(&self, package_dir: &Path) -> Result<Vec<PathBuf>> {
+        list_entries(package_dir, ARTIFACT_EXCLUDES)
+    }
+
+    fn dependency_dirs(&self) -> &'static [&'static str] {
+        &[CACHE_DIR]
+    }
+}
+
+/// The entrypoint from `exports`, the file run by the `start` task, or a
+/// conventional entry file.
+fn resolve_entrypoint(project_path: &Path, config: &DenoConfig) -> Option<String> {
+    let exported = match &config.exports {
+        Some(serde_json::Value::String(path)) => Some(path.as_str()),
+        Some(exports) => exports.get(".").and_then(|path| path.as_str()),
+        None => None,
+    };
+    let started = config.task("start").and_then(|command| {
+        command.split_whitespace().find(|arg| {
+            [".ts", ".tsx", ".js", ".jsx", ".mjs"]
+                .iter()
+                .any(|extension| arg.ends_with(extension))
+        })
+    });
+
+    exported
+        .or(started)
+        .map(|path| path.trim_start_matches("./").to_string())
+        .or_else(|| {
+            ENTRYPOINTS
+                .iter()
+                .find(|file| project_path.join(file).is_file())
+                .map(|file| file.to_string())
+        })
+}
+
+fn major_version(version: &str) -> Option<String> {
+    let major: String = version
+        .trim()
+        .trim_start_matches('v')
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    (!major.is_empty()).then_some(major)
+}
+
+/// Turn JSONC into JSON by removing comments and trailing commas.
+fn strip_jsonc(content: &str) -> String {
+    let mut json = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&next| next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            ('}' | ']', _) => {
+                // Drop a trailing comma before the closing bracket
+                let trimmed = json.trim_end().len();
+                if json[..trimmed].ends_with(',') {
+                    json.truncate(trimmed - 1);
+                }
+                json.push(c);
+            }
+            _ => json.push(c),
+        }
+    }
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = r#"{
+            // Local tasks
+            "tasks": { "start": "deno run main.ts", }, /* trailing */
+            "url": "https://example.com/*not-a-comment*/",
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(jsonc)).unwrap();
+        assert_eq!(value["tasks"]["start"], "deno run main.ts");
+        assert_eq!(value["url"], "https://example.com/*not-a-comment*/");
+    }
+
+    #[test]
+    fn test_resolve_entrypoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = |json: &str| serde_json::from_str::<DenoConfig>(json).unwrap();
+
+        assert_eq!(
+            resolve_entrypoint(dir.path(), &config(r#"{"exports": "./src/mod.ts"}"#)).as_deref(),
+            Some("src/mod.ts")
+        );
+        assert_eq!(
+            resolve_entrypoint(
+                dir.path(),
+                &config(r#"{"tasks": {"start": {"command": "deno run -A server.ts"}}}"#)
+            )
+            .as_deref(),
+            Some("server.ts")
+        );
+        assert_eq!(resolve_entrypoint(dir.path(), &config("{}")), None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Language runtimes the builder knows how to install, build and package.
+//!
+//! Each runtime detects its projects from the files in the project
+//! directory; [`detect`] tries them in priority order.
+
+mod container;
+mod deno;
+mod node;
+mod python;
+mod static_site;
+
+pub use container::{ContainerRuntime, ImageTool};
+pub use deno::DenoRuntime;
+pub use node::{Engines, NodeRuntime, PackageJson};
+pub use python::PythonRuntime;
+pub use static_site::{Framework, StaticRuntime};
+
+use crate::api::{ArtifactType, StaticSiteConfig};
+use crate::builder::{ProjectBuilder, StagingDir};
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use std::path::{Path, PathBuf};
+
+/// Files that mark the root of a project of some supported runtime.
+pub const PROJECT_MARKERS: &[&str] = &[
+    "package.json",
+    "deno.json",
+    "deno.jsonc",
+    "pyproject.toml",
+    "requirements.txt",
+    "Pipfile",
+    "index.html",
+];
+
+pub trait Runtime: Send + Sync {
+    /// Name used in build output, e.g. "Node.js".
+    fn display_name(&self) -> &'static str;
+
+    fn app_name(&self) -> &str;
+
+    fn version(&self) -> Option<&str>;
+
+    /// Runtime identifier sent to the platform, e.g. `node:20`.
+    fn runtime_id(&self) -> String;
+
+    /// Command the platform runs to start the application, if one can be
+    /// derived from the project.
+    fn start_command(&self) -> Option<String>;
+
+    /// File the start command runs, relative to the packaged directory.
+    fn entry_file(&self) -> Option<String> {
+        self.start_command()
+            .as_deref()
+            .and_then(entry_file_in_command)
+    }
+
+    /// Directory holding everything needed to build the project. Clean
+    /// builds copy it into a staging directory first.
+    fn source_root(&self) -> &Path;
+
+    /// Install dependencies and build the project inside `root` (the source
+    /// root or its staging copy), returning the directory to package.
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>>;
+
+    /// Files and directories, relative to the packaged directory, that go
+    /// into the artifact.
+    fn artifact_paths(&self, package_dir: &Path) -> Result<Vec<PathBuf>>;
+
+    /// Top-level directories of installed dependencies, which can be
+    /// deployed as a separate layer that is only uploaded when the
+    /// lockfile changes.
+    fn dependency_dirs(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Kind of artifact the build produces.
+    fn artifact_type(&self) -> ArtifactType {
+        ArtifactType::Tarball
+    }
+
+    /// Serving rules sent with the deployment when the project is a static
+    /// site.
+    fn static_site(&self) -> Option<StaticSiteConfig> {
+        None
+    }
+}
+
+/// A built project ready to be packaged, or an archive the runtime built
+/// itself.
+pub struct PackageDir {
+    path: PathBuf,
+    is_archive: bool,
+    // Keeps a temporary bundle directory alive until the artifact is written
+    _staging: Option<StagingDir>,
+}
+
+impl PackageDir {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            is_archive: false,
+            _staging: None,
+        }
+    }
+
+    pub(crate) fn staged(staging: StagingDir) -> Self {
+        Self {
+            path: staging.path().to_path_buf(),
+            is_archive: false,
+            _staging: Some(staging),
+        }
+    }
+
+    /// A finished artifact at `file_name` in `staging`, used as-is.
+    pub(crate) fn archive(staging: StagingDir, file_name: &str) -> Self {
+        Self {
+            path: staging.path().join(file_name),
+            is_archive: true,
+            _staging: Some(staging),
+        }
+    }
+
+    pub fn is_archive(&self) -> bool {
+        self.is_archive
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+type Detector = fn(&Path) -> Result<Option<Box<dyn Runtime>>>;
+
+/// Runtime detectors in priority order. Deno projects may also have a
+/// package.json, and frontend builds must be recognized before the Node.js
+/// server fallback, while plain HTML sites come last so server projects that
+/// happen to contain an index.html keep their runtime.
+const DETECTORS: &[Detector] = &[
+    |path| Ok(DenoRuntime::detect(path)?.map(|r| Box::new(r) as Box<dyn Runtime>)),
+    |path| Ok(StaticRuntime::detect(path)?.map(|r| Box::new(r) as Box<dyn Runtime>)),
+    |path| Ok(NodeRuntime::detect(path)?.map(|r| Box::new(r) as Box<dyn Runtime>)),
+    |path| Ok(PythonRuntime::detect(path)?.map(|r| Box::new(r) as Box<dyn Runtime>)),
+    |path| Ok(StaticRuntime::detect_plain(path)?.map(|r| Box::new(r) as Box<dyn Runtime>)),
+];
+
+/// Detect the runtime of the project in `project_path`.
+pub fn detect(project_path: &Path) -> Result<Box<dyn Runtime>> {
+    for detector in DETECTORS {
+        if let Some(runtime) = detector(project_path)? {
+            return Ok(runtime);
+        }
+    }
+
+    Err(AetherError::invalid_project(format!(
+        "No supported project found in {} (looked for {})",
+        project_path.display(),
+        PROJECT_MARKERS.join(", ")
+    )))
+}
+
+/// Whether `dir` contains a file marking a project root.
+pub fn is_project_root(dir: &Path) -> bool {
+    PROJECT_MARKERS
+        .iter()
+        .any(|marker| dir.join(marker).is_file())
+}
+
+/// Name of the directory at `path`, used when the project declares none.
+pub(crate) fn directory_name(path: &Path) -> String {
+    path.canonicalize()
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| "app".to_string())
+}
+
+/// Entries of `dir` whose names are not in `excludes`, sorted by name.
+pub(crate) fn list_entries(dir: &Path, excludes: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        if !excludes.iter().any(|excluded| name == *excluded) {
+            entries.push(PathBuf::from(name));
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// The script file a start command runs, e.g. `dist/server.js` for
+/// `node --enable-source-maps dist/server.js`.
+pub fn entry_file_in_command(command: &str) -> Option<String> {
+    const SCRIPT_EXTENSIONS: &[&str] = &[".js", ".mjs", ".cjs", ".ts", ".mts", ".py"];
+
+    command
+        .split_whitespace()
+        .map(|arg| arg.trim_matches(|c| c == '"' || c == '\''))
+        .find(|arg| {
+            !arg.starts_with('-')
+                && !arg.contains('=')
+                && SCRIPT_EXTENSIONS.iter().any(|ext| arg.ends_with(ext))
+        })
+        .map(|arg| arg.trim_start_matches("./").to_string())
+}
+
+/// The `web` process declared in a Procfile in `dir`, if any.
+pub(crate) fn procfile_web_command(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("Procfile")).ok()?;
+    content.lines().find_map(|line| {
+        let (process, command) = line.split_once(':')?;
+        (process.trim() == "web" && !command.trim().is_empty()).then(|| command.trim().to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_file_in_command() {
+        assert_eq!(
+            entry_file_in_command("node --enable-source-maps ./dist/server.js").as_deref(),
+            Some("dist/server.js")
+        );
+        assert_eq!(
+            entry_file_in_command("NODE_ENV=production node index.mjs").as_deref(),
+            Some("index.mjs")
+        );
+        assert_eq!(entry_file_in_command("python -m gunicorn app:app"), None);
+        assert_eq!(entry_file_in_command("next start"), None);
+    }
+}
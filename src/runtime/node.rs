@@ -0,0 +1,739 @@
+use super::{entry_file_in_command, procfile_web_command, PackageDir, Runtime};
+use crate::builder::{
+    copy_dir_excluding, run_blocking, ProjectBuilder, StagingDir, STAGING_EXCLUDES,
+};
+use crate::workspace::Workspace;
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+#[derive(Debug, Deserialize)]
+pub struct PackageJson {
+    pub name: String,
+    pub version: Option<String>,
+    pub main: Option<String>,
+    pub scripts: Option<HashMap<String, String>>,
+    pub dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "devDependencies")]
+    pub dev_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "optionalDependencies")]
+    pub optional_dependencies: Option<HashMap<String, String>>,
+    #[serde(rename = "peerDependencies")]
+    pub peer_dependencies: Option<HashMap<String, String>>,
+    pub engines: Option<Engines>,
+}
+
+impl PackageJson {
+    /// Dependencies needed at runtime, paired with whether they must be
+    /// installed (optional and peer dependencies may be absent).
+    pub fn runtime_dependencies(&self) -> Vec<(&str, bool)> {
+        let mut dependencies: Vec<(&str, bool)> = Vec::new();
+        for (map, required) in [
+            (&self.dependencies, true),
+            (&self.optional_dependencies, false),
+            (&self.peer_dependencies, false),
+        ] {
+            if let Some(map) = map {
+                dependencies.extend(map.keys().map(|name| (name.as_str(), required)));
+            }
+        }
+        // Stable order keeps hoisting decisions reproducible; a package
+        // listed as both regular and optional stays required
+        dependencies.sort_by(|a, b| a.0.cmp(b.0).then(b.1.cmp(&a.1)));
+        dependencies.dedup_by(|a, b| a.0 == b.0);
+        dependencies
+    }
+
+    pub fn runtime_dependency_names(&self) -> impl Iterator<Item = &str> {
+        self.runtime_dependencies()
+            .into_iter()
+            .map(|(name, _)| name)
+    }
+
+    /// Whether `name` is a regular or dev dependency.
+    pub fn depends_on(&self, name: &str) -> bool {
+        [&self.dependencies, &self.dev_dependencies]
+            .into_iter()
+            .flatten()
+            .any(|map| map.contains_key(name))
+    }
+
+    /// The first of the conventional build script names defined in
+    /// package.json.
+    pub fn build_script_name(&self) -> Option<&'static str> {
+        let scripts = self.scripts.as_ref()?;
+        ["build", "compile", "prepare"]
+            .into_iter()
+            .find(|name| scripts.contains_key(*name))
+    }
+}
+
+/// Entry files tried when package.json has no start script or `main`.
+const CONVENTIONAL_ENTRIES: &[&str] = &["index.js", "server.js", "app.js", "main.js"];
+
+#[derive(Debug, Deserialize)]
+pub struct Engines {
+    pub node: Option<String>,
+    pub npm: Option<String>,
+}
+
+/// Node.js projects built with npm, yarn or pnpm, optionally as a single
+/// package of a workspace.
+pub struct NodeRuntime {
+    project_path: PathBuf,
+    package_json: PackageJson,
+    workspace: Option<Workspace>,
+}
+
+impl NodeRuntime {
+    pub fn detect(project_path: &Path) -> Result<Option<Self>> {
+        let package_json_path = project_path.join("package.json");
+        if !package_json_path.is_file() {
+            return Ok(None);
+        }
+
+        let package_json_content = std::fs::read_to_string(&package_json_path)?;
+        let package_json: PackageJson = serde_json::from_str(&package_json_content)?;
+
+        Ok(Some(Self {
+            project_path: project_path.to_path_buf(),
+            package_json,
+            workspace: None,
+        }))
+    }
+
+    /// A single package of the npm/yarn/pnpm workspace containing
+    /// `start_dir`, built together with its local workspace dependencies.
+    pub fn for_workspace(start_dir: &Path, package_name: &str) -> Result<Self> {
+        let workspace = Workspace::discover(start_dir)?
+            .ok_or_else(|| AetherError::invalid_project("No npm, yarn or pnpm workspace found"))?;
+
+        let member = workspace.member(package_name).ok_or_else(|| {
+            let available: Vec<&str> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+            AetherError::invalid_project(format!(
+                "Workspace package '{}' not found (available: {})",
+                package_name,
+                available.join(", ")
+            ))
+        })?;
+
+        let project_path = workspace.root.join(&member.relative_path);
+        let mut runtime = Self::detect(&project_path)?.ok_or_else(|| {
+            AetherError::invalid_project("No package.json found in project directory")
+        })?;
+        runtime.workspace = Some(workspace);
+        Ok(runtime)
+    }
+
+    pub fn project_path(&self) -> &Path {
+        &self.project_path
+    }
+
+    pub fn package_json(&self) -> &PackageJson {
+        &self.package_json
+    }
+
+    pub fn node_version(&self) -> String {
+        self.package_json
+            .engines
+            .as_ref()
+            .and_then(|e| e.node.as_deref())
+            .unwrap_or("20")
+            .to_string()
+    }
+
+    fn start_script(&self) -> Option<&str> {
+        self.package_json
+            .scripts
+            .as_ref()?
+            .get("start")
+            .map(String::as_str)
+    }
+
+    /// The file `node` runs when there is no start script: package.json
+    /// `main` or a conventional entry file.
+    fn main_entry(&self) -> Option<String> {
+        if let Some(main) = &self.package_json.main {
+            let main = main.trim_start_matches("./");
+            // Node resolves `main` like require() does
+            let resolved = [
+                main.to_string(),
+                format!("{}.js", main),
+                format!("{}/index.js", main.trim_end_matches('/')),
+            ]
+            .into_iter()
+            .find(|candidate| self.project_path.join(candidate).is_file());
+
+            match resolved {
+                Some(entry) => return Some(entry),
+                // `main` may point at output the build has yet to produce
+                None if self.package_json.build_script_name().is_some() => {
+                    return Some(main.to_string())
+                }
+                None => {}
+            }
+        }
+
+        CONVENTIONAL_ENTRIES
+            .iter()
+            .find(|file| self.project_path.join(file).is_file())
+            .map(|file| file.to_string())
+    }
+
+    fn detect_package_manager(&self) -> String {
+        // Workspace lockfiles live at the workspace root
+        let lockfile_dir = self
+            .workspace
+            .as_ref()
+            .map(|workspace| workspace.root.as_path())
+            .unwrap_or(&self.project_path);
+
+        // Check for lock files to determine package manager
+        if lockfile_dir.join("yarn.lock").exists() {
+            "yarn".to_string()
+        } else if lockfile_dir.join("pnpm-lock.yaml").exists()
+            || self.workspace.as_ref().is_some_and(|w| w.uses_pnpm())
+        {
+            "pnpm".to_string()
+        } else {
+            "npm".to_string()
+        }
+    }
+
+    /// The package.json build script, run unless aether.json configures a
+    /// build command.
+    pub(crate) fn build_script(&self, work_dir: &Path) -> Option<(&'static str, Command)> {
+        let script = self.package_json.build_script_name()?;
+        let mut cmd = Command::new(self.detect_package_manager());
+        cmd.current_dir(work_dir);
+        cmd.args(["run", script]);
+        Some((script, cmd))
+    }
+
+    async fn build_project(&self, builder: &ProjectBuilder, root: &Path) -> Result<PackageDir> {
+        if builder.is_clean() {
+            self.install_frozen_dependencies(builder, root).await?;
+        } else {
+            self.install_dependencies(builder, root, true).await?;
+        }
+
+        builder
+            .run_build_steps(root, self.build_script(root))
+            .await?;
+
+        if builder.is_clean() {
+            self.prune_dev_dependencies(builder, root).await?;
+        }
+
+        Ok(PackageDir::new(root))
+    }
+
+    async fn build_workspace_package(
+        &self,
+        builder: &ProjectBuilder,
+        workspace: &Workspace,
+        root: &Path,
+    ) -> Result<PackageDir> {
+        let name = self.package_json.name.as_str();
+        let member_path = self
+            .project_path
+            .strip_prefix(&workspace.root)
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let package_dir = root.join(&member_path);
+
+        builder.output(&format!(
+            "🗂️  Workspace: {} (package {})",
+            workspace.root.display(),
+            member_path.display()
+        ));
+
+        self.install_workspace_dependencies(builder, root).await?;
+
+        // Local packages are built first so the target can use their output
+        if !builder.skips_build() {
+            for dependency in workspace.local_dependencies(name) {
+                let Some(script) = dependency.package_json.build_script_name() else {
+                    continue;
+                };
+                builder.output(&format!(
+                    "🏗️  Building workspace dependency '{}'...",
+                    dependency.name
+                ));
+                let mut cmd = Command::new(self.detect_package_manager());
+                cmd.current_dir(root.join(&dependency.relative_path));
+                cmd.args(["run", script]);
+                builder
+                    .run_command(cmd, "Building dependency...", "Dependency build failed")
+                    .await?;
+            }
+        }
+        builder
+            .run_build_steps(&package_dir, self.build_script(&package_dir))
+            .await?;
+
+        // Package the target with only the dependencies it needs at runtime
+        let bundle = StagingDir::create(name)?;
+        let (root, bundle_dir) = (root.to_path_buf(), bundle.path().to_path_buf());
+        let bundled = run_blocking(move || {
+            copy_dir_excluding(&package_dir, &bundle_dir, STAGING_EXCLUDES)?;
+            crate::workspace::bundle_production_dependencies(&package_dir, &root, &bundle_dir)
+        })
+        .await?;
+        builder.output(&format!("📦 Bundled {} runtime dependencies", bundled));
+
+        Ok(PackageDir::staged(bundle))
+    }
+
+    /// Install the workspace's dependencies, limited to the target package
+    /// where the package manager supports it. Dev dependencies are included
+    /// for the build; only runtime dependencies end up in the artifact.
+    async fn install_workspace_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        root: &Path,
+    ) -> Result<()> {
+        builder.output("📦 Installing workspace dependencies...");
+
+        let name = self.package_json.name.as_str();
+        let package_manager = self.detect_package_manager();
+        let mut cmd = self.install_command(builder, root);
+
+        match package_manager.as_str() {
+            "pnpm" => {
+                cmd.args(["install", "--filter", &format!("{}...", name)]);
+                if builder.is_clean() {
+                    cmd.arg("--frozen-lockfile");
+                }
+            }
+            // Yarn classic cannot install a single workspace
+            "yarn" => {
+                cmd.arg("install");
+                if builder.is_clean() {
+                    cmd.arg("--frozen-lockfile");
+                }
+            }
+            _ => {
+                cmd.args([
+                    if builder.is_clean() { "ci" } else { "install" },
+                    "--workspace",
+                    name,
+                ]);
+            }
+        }
+
+        builder
+            .run_command(
+                cmd,
+                "Installing dependencies...",
+                "Failed to install dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dependencies installed successfully");
+        Ok(())
+    }
+
+    /// The package manager, set up to install binaries for the target
+    /// platform when cross-installing.
+    fn install_command(&self, builder: &ProjectBuilder, work_dir: &Path) -> Command {
+        let mut cmd = Command::new(self.detect_package_manager());
+        cmd.current_dir(work_dir);
+        if let Some(platform) = builder.cross_install_platform() {
+            // os/cpu select platform-specific optional dependencies;
+            // platform/arch pick the binaries prebuild-install and
+            // node-pre-gyp download
+            for (key, value) in [
+                ("npm_config_os", platform.os_name()),
+                ("npm_config_cpu", platform.arch_name()),
+                ("npm_config_platform", platform.os_name()),
+                ("npm_config_arch", platform.arch_name()),
+            ] {
+                cmd.env(key, value);
+            }
+        }
+        cmd
+    }
+
+    /// Install everything the build needs, dev dependencies included, for
+    /// projects that only ship their build output.
+    pub(crate) async fn install_build_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        work_dir: &Path,
+    ) -> Result<()> {
+        if builder.is_clean() {
+            self.install_frozen_dependencies(builder, work_dir).await
+        } else {
+            self.install_dependencies(builder, work_dir, false).await
+        }
+    }
+
+    async fn install_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        work_dir: &Path,
+        production: bool,
+    ) -> Result<()> {
+        builder.output("📦 Installing dependencies...");
+
+        // Check if node_modules exists and has content
+        let node_modules = work_dir.join("node_modules");
+        if node_modules.exists() && std::fs::read_dir(&node_modules)?.count() > 0 {
+            builder.output("📦 Dependencies already installed, skipping...");
+            return Ok(());
+        }
+
+        // Determine package manager
+        let package_manager = self.detect_package_manager();
+
+        let mut cmd = self.install_command(builder, work_dir);
+
+        cmd.arg("install");
+        if production {
+            cmd.arg(if package_manager == "pnpm" {
+                "--prod"
+            } else {
+                "--production"
+            });
+        }
+
+        builder
+            .run_command(
+                cmd,
+                "Installing dependencies...",
+                "Failed to install dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dependencies installed successfully");
+        Ok(())
+    }
+
+    /// Install the exact dependency tree from the lockfile, including dev
+    /// dependencies needed by the build script.
+    async fn install_frozen_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        work_dir: &Path,
+    ) -> Result<()> {
+        builder.output("📦 Installing dependencies from lockfile...");
+
+        let package_manager = self.detect_package_manager();
+        let lockfile = match package_manager.as_str() {
+            "yarn" => "yarn.lock",
+            "pnpm" => "pnpm-lock.yaml",
+            _ => "package-lock.json",
+        };
+        if !work_dir.join(lockfile).exists() {
+            return Err(AetherError::build(format!(
+                "Clean builds require a lockfile, but {} was not found",
+                lockfile
+            )));
+        }
+
+        let mut cmd = self.install_command(builder, work_dir);
+
+        match package_manager.as_str() {
+            "yarn" | "pnpm" => {
+                cmd.args(["install", "--frozen-lockfile"]);
+            }
+            _ => {
+                cmd.arg("ci");
+            }
+        }
+
+        builder
+            .run_command(
+                cmd,
+                "Installing dependencies...",
+                "Failed to install dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dependencies installed successfully");
+        Ok(())
+    }
+
+    /// Remove dev dependencies from `node_modules` after the build so they
+    /// are not shipped in the artifact.
+    async fn prune_dev_dependencies(
+        &self,
+        builder: &ProjectBuilder,
+        work_dir: &Path,
+    ) -> Result<()> {
+        let package_manager = self.detect_package_manager();
+
+        let mut cmd = self.install_command(builder, work_dir);
+
+        match package_manager.as_str() {
+            // Yarn classic has no prune; reinstalling in production mode
+            // removes the dev-only packages.
+            "yarn" => {
+                cmd.args([
+                    "install",
+                    "--frozen-lockfile",
+                    "--production",
+                    "--ignore-scripts",
+                ]);
+            }
+            "pnpm" => {
+                cmd.args(["prune", "--prod"]);
+            }
+            _ => {
+                cmd.args(["prune", "--omit=dev"]);
+            }
+        }
+
+        builder
+            .run_command(
+                cmd,
+                "Pruning dev dependencies...",
+                "Failed to prune dev dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dev dependencies pruned");
+        Ok(())
+    }
+}
+
+impl Runtime for NodeRuntime {
+    fn display_name(&self) -> &'static str {
+        "Node.js"
+    }
+
+    fn app_name(&self) -> &str {
+        &self.package_json.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.package_json.version.as_deref()
+    }
+
+    fn runtime_id(&self) -> String {
+        let node_version = self.node_version();
+        // Extract major version number
+        if let Ok(major) = node_version
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse::<u32>()
+        {
+            format!("node:{}", major)
+        } else {
+            "node:20".to_string()
+        }
+    }
+
+    fn start_command(&self) -> Option<String> {
+        if let Some(command) = procfile_web_command(&self.project_path) {
+            return Some(command);
+        }
+        if self.start_script().is_some() {
+            return Some(format!("{} start", self.detect_package_manager()));
+        }
+        self.main_entry().map(|entry| format!("node {}", entry))
+    }
+
+    fn entry_file(&self) -> Option<String> {
+        if let Some(command) = procfile_web_command(&self.project_path) {
+            return entry_file_in_command(&command);
+        }
+        match self.start_script() {
+            Some(script) => entry_file_in_command(script),
+            None => self.main_entry(),
+        }
+    }
+
+    fn source_root(&self) -> &Path {
+        // Workspace packages need the whole workspace to install and build
+        self.workspace
+            .as_ref()
+            .map(|workspace| workspace.root.as_path())
+            .unwrap_or(&self.project_path)
+    }
+
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>> {
+        Box::pin(async move {
+            match &self.workspace {
+                Some(workspace) => self.build_workspace_package(builder, workspace, root).await,
+                None => self.build_project(builder, root).await,
+            }
+        })
+    }
+
+    fn artifact_paths(&self, package_dir: &Path) -> Result<Vec<PathBuf>> {
+        // Manifest and lockfiles, dependencies, common source directories
+        // and entry points
+        let candidates = [
+            "package.json",
+            "package-lock.json",
+            "yarn.lock",
+            "pnpm-lock.yaml",
+            "node_modules",
+            "src",
+            "lib",
+            "dist",
+            "build",
+            "public",
+            "views",
+            "index.js",
+            "server.js",
+            "app.js",
+            "main.js",
+            ".env.example",
+        ];
+
+        Ok(candidates
+            .into_iter()
+            .map(PathBuf::from)
+            .filter(|path| package_dir.join(path).exists())
+            .collect())
+    }
+
+    fn dependency_dirs(&self) -> &'static [&'static str] {
+        // Workspace node_modules links to local packages, which are
+        // application code
+        if self.workspace.is_some() {
+            &[]
+        } else {
+            &["node_modules"]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_project(dir: &Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            std::fs::write(dir.join(path), content).unwrap();
+        }
+    }
+
+    /// Build the project in `dir`, returning the lines it printed.
+    async fn build(dir: &Path, skip_build: bool) -> Vec<String> {
+        let lines = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = lines.clone();
+        ProjectBuilder::new(dir)
+            .unwrap()
+            .with_build_log(dir.join("build.log"))
+            .with_skip_build(skip_build)
+            .with_output_callback(move |line| sink.lock().unwrap().push(line.to_string()))
+            .build(Some(dir.join("app.tar.gz")))
+            .await
+            .unwrap();
+        let lines = std::mem::take(&mut *lines.lock().unwrap());
+        lines
+    }
+
+    #[test]
+    fn test_build_script_name() {
+        let package_json = |json: &str| {
+            serde_json::from_str::<PackageJson>(json)
+                .unwrap()
+                .build_script_name()
+        };
+        assert_eq!(
+            package_json(r#"{"name":"api","scripts":{"compile":"tsc"}}"#),
+            Some("compile")
+        );
+        assert_eq!(
+            package_json(r#"{"name":"api","scripts":{"compile":"tsc","build":"vite build"}}"#),
+            Some("build")
+        );
+        assert_eq!(
+            package_json(r#"{"name":"api","scripts":{"start":"node index.js"}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_runtime_dependencies_keep_required_duplicates() {
+        let package_json: PackageJson = serde_json::from_str(
+            r#"{
+                "name": "api",
+                "dependencies": {"react": "^18", "express": "^4"},
+                "peerDependencies": {"react": "^18"},
+                "optionalDependencies": {"fsevents": "^2", "express": "^4"}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            package_json.runtime_dependencies(),
+            [("express", true), ("fsevents", false), ("react", true)]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_build_steps_run_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[
+                (
+                    "package.json",
+                    r#"{"name":"api","version":"1.0.0","scripts":{"compile":"echo compile >> order.txt"}}"#,
+                ),
+                ("index.js", ""),
+                (
+                    "aether.json",
+                    r#"{"build":{"pre_build":["echo pre >> order.txt"],"post_build":["echo post >> order.txt"]}}"#,
+                ),
+            ],
+        );
+
+        // A project with only a compile script runs it as the build script
+        let lines = build(dir.path(), false).await;
+        assert!(lines.contains(&"🏗️  Running build script 'compile'...".to_string()));
+        let order = std::fs::read_to_string(dir.path().join("order.txt")).unwrap();
+        assert_eq!(order, "pre\ncompile\npost\n");
+
+        // A build command replaces the script
+        std::fs::remove_file(dir.path().join("order.txt")).unwrap();
+        write_project(
+            dir.path(),
+            &[(
+                "aether.json",
+                r#"{"build":{"pre_build":["echo pre >> order.txt"],"command":"echo command >> order.txt","post_build":["echo post >> order.txt"]}}"#,
+            )],
+        );
+        build(dir.path(), false).await;
+        let order = std::fs::read_to_string(dir.path().join("order.txt")).unwrap();
+        assert_eq!(order, "pre\ncommand\npost\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_no_build_skips_build_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        write_project(
+            dir.path(),
+            &[
+                (
+                    "package.json",
+                    r#"{"name":"api","version":"1.0.0","scripts":{"build":"echo build >> order.txt"}}"#,
+                ),
+                ("index.js", ""),
+                (
+                    "aether.json",
+                    r#"{"build":{"pre_build":["echo pre >> order.txt"],"post_build":["echo post >> order.txt"]}}"#,
+                ),
+            ],
+        );
+
+        let lines = build(dir.path(), true).await;
+        assert!(lines.contains(&"⏭️  Skipping build, packaging prebuilt files".to_string()));
+        assert!(!dir.path().join("order.txt").exists());
+    }
+}
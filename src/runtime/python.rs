@@ -0,0 +1,450 @@
+use super::{directory_name, list_entries, procfile_web_command, PackageDir, Runtime};
+use crate::builder::{shell_command, ProjectBuilder, StagingDir};
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Directory the dependencies are vendored into. The platform adds it to
+/// `PYTHONPATH` when starting the application.
+pub const VENDOR_DIR: &str = ".python_packages";
+
+const DEFAULT_PYTHON_VERSION: &str = "3.12";
+
+/// Entries of the project directory left out of the artifact.
+const ARTIFACT_EXCLUDES: &[&str] = &[
+    ".git",
+    ".aether",
+    ".env",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".pytest_cache",
+    ".mypy_cache",
+    ".ruff_cache",
+    ".tox",
+    "node_modules",
+];
+
+#[derive(Debug, Default, Deserialize)]
+struct PyProject {
+    project: Option<ProjectTable>,
+    tool: Option<ToolTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectTable {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(rename = "requires-python")]
+    requires_python: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolTable {
+    poetry: Option<PoetryTable>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoetryTable {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Pipfile {
+    #[serde(default)]
+    packages: BTreeMap<String, toml::Value>,
+    requires: Option<PipfileRequires>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipfileRequires {
+    python_version: Option<String>,
+    python_full_version: Option<String>,
+}
+
+/// Where the dependencies to vendor are declared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DependencySource {
+    Requirements,
+    Pipfile,
+    PyProject,
+}
+
+/// Python applications whose dependencies are declared in
+/// `requirements.txt`, a `Pipfile` or `pyproject.toml`.
+pub struct PythonRuntime {
+    project_path: PathBuf,
+    name: String,
+    version: Option<String>,
+    python_version: String,
+    dependency_source: DependencySource,
+    /// Normalized names of the declared dependencies
+    dependencies: Vec<String>,
+}
+
+impl PythonRuntime {
+    pub fn detect(project_path: &Path) -> Result<Option<Self>> {
+        let pyproject_path = project_path.join("pyproject.toml");
+        let pipfile_path = project_path.join("Pipfile");
+        let requirements_path = project_path.join("requirements.txt");
+
+        // requirements.txt wins since it usually pins exactly what to install
+        let dependency_source = if requirements_path.is_file() {
+            DependencySource::Requirements
+        } else if pipfile_path.is_file() {
+            DependencySource::Pipfile
+        } else if pyproject_path.is_file() {
+            DependencySource::PyProject
+        } else {
+            return Ok(None);
+        };
+
+        let pyproject: PyProject = read_toml(&pyproject_path)?.unwrap_or_default();
+        let pipfile: Pipfile = read_toml(&pipfile_path)?.unwrap_or_default();
+        let project = pyproject.project.as_ref();
+        let poetry = pyproject
+            .tool
+            .as_ref()
+            .and_then(|tool| tool.poetry.as_ref());
+
+        let name = project
+            .and_then(|p| p.name.clone())
+            .or_else(|| poetry.and_then(|p| p.name.clone()))
+            .unwrap_or_else(|| directory_name(project_path));
+        let version = project
+            .and_then(|p| p.version.clone())
+            .or_else(|| poetry.and_then(|p| p.version.clone()));
+
+        let dependencies = match dependency_source {
+            DependencySource::Requirements => std::fs::read_to_string(&requirements_path)?
+                .lines()
+                .filter_map(requirement_name)
+                .collect(),
+            DependencySource::Pipfile => pipfile.packages.keys().map(|n| normalize(n)).collect(),
+            DependencySource::PyProject => project
+                .map(|p| {
+                    p.dependencies
+                        .iter()
+                        .filter_map(|d| requirement_name(d))
+                        .collect()
+                })
+                .or_else(|| {
+                    poetry.map(|p| {
+                        p.dependencies
+                            .keys()
+                            .filter(|name| *name != "python")
+                            .map(|n| normalize(n))
+                            .collect()
+                    })
+                })
+                .unwrap_or_default(),
+        };
+
+        let python_version = detect_python_version(project_path, &pyproject, &pipfile)?;
+
+        Ok(Some(Self {
+            project_path: project_path.to_path_buf(),
+            name,
+            version,
+            python_version,
+            dependency_source,
+            dependencies,
+        }))
+    }
+
+    pub fn python_version(&self) -> &str {
+        &self.python_version
+    }
+
+    fn depends_on(&self, package: &str) -> bool {
+        self.dependencies.iter().any(|name| name == package)
+    }
+
+    /// Install the dependencies into [`VENDOR_DIR`] so the artifact runs
+    /// without network access.
+    async fn vendor_dependencies(&self, builder: &ProjectBuilder, root: &Path) -> Result<()> {
+        builder.output(&format!("📦 Vendoring dependencies into {}...", VENDOR_DIR));
+
+        // Start from scratch so removed requirements do not linger
+        let vendor_dir = root.join(VENDOR_DIR);
+        if vendor_dir.exists() {
+            std::fs::remove_dir_all(&vendor_dir)?;
+        }
+
+        let mut cmd = Command::new(python_command());
+        cmd.current_dir(root);
+        cmd.args([
+            "-m",
+            "pip",
+            "install",
+            "--disable-pip-version-check",
+            "--no-input",
+            // Bytecode embeds source timestamps; Python compiles it at startup
+            "--no-compile",
+            "--target",
+            VENDOR_DIR,
+        ]);
+        if let Some(platform) = builder.cross_install_platform() {
+            // Only prebuilt wheels can be installed for another platform
+            for tag in platform.python_platform_tags() {
+                cmd.arg("--platform").arg(tag);
+            }
+            cmd.args(["--only-binary=:all:", "--implementation", "cp"]);
+            cmd.args(["--python-version", self.python_version()]);
+        }
+
+        // Pipfile.lock is exported to a requirements file pip understands
+        let mut _export = None;
+        match self.dependency_source {
+            DependencySource::Requirements => {
+                cmd.args(["-r", "requirements.txt"]);
+            }
+            DependencySource::Pipfile => {
+                let export = StagingDir::create(&self.name)?;
+                let requirements = export.path().join("requirements.txt");
+                builder
+                    .run_command(
+                        shell_command(
+                            &format!("pipenv requirements > \"{}\"", requirements.display()),
+                            root,
+                        ),
+                        "Exporting Pipfile.lock...",
+                        "Failed to export Pipfile dependencies",
+                    )
+                    .await?;
+                cmd.arg("-r").arg(&requirements);
+                _export = Some(export);
+            }
+            DependencySource::PyProject => {
+                cmd.arg(".");
+            }
+        }
+
+        builder
+            .run_command(
+                cmd,
+                "Installing dependencies...",
+                "Failed to install dependencies",
+            )
+            .await?;
+
+        builder.output("✅ Dependencies installed successfully");
+        Ok(())
+    }
+}
+
+impl Runtime for PythonRuntime {
+    fn display_name(&self) -> &'static str {
+        "Python"
+    }
+
+    fn app_name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    fn runtime_id(&self) -> String {
+        format!("python:{}", self.python_version)
+    }
+
+    fn start_command(&self) -> Option<String> {
+        if let Some(command) = procfile_web_command(&self.project_path) {
+            return Some(command);
+        }
+
+        // Vendored console scripts have the build machine's interpreter in
+        // their shebang, so servers are started as modules
+        for module in ["main", "app", "wsgi", "asgi", "server"] {
+            if !self.project_path.join(format!("{}.py", module)).is_file() {
+                continue;
+            }
+            let command = if self.depends_on("uvicorn") && module != "wsgi" {
+                format!("python -m uvicorn {}:app --host 0.0.0.0", module)
+            } else if self.depends_on("gunicorn") && module != "asgi" {
+                format!("python -m gunicorn {}:app", module)
+            } else {
+                format!("python {}.py", module)
+            };
+            return Some(command);
+        }
+
+        // Django projects keep wsgi.py in the settings package
+        if self.project_path.join("manage.py").is_file() && self.depends_on("gunicorn") {
+            let settings_package = std::fs::read_dir(&self.project_path)
+                .ok()?
+                .filter_map(|entry| entry.ok())
+                .find(|entry| entry.path().join("wsgi.py").is_file())?;
+            return Some(format!(
+                "python -m gunicorn {}.wsgi",
+                settings_package.file_name().to_string_lossy()
+            ));
+        }
+
+        None
+    }
+
+    fn source_root(&self) -> &Path {
+        &self.project_path
+    }
+
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>> {
+        Box::pin(async move {
+            self.vendor_dependencies(builder, root).await?;
+            builder.run_build_steps(root, None).await?;
+            Ok(PackageDir::new(root))
+        })
+    }
+
+    fn artifact_paths(&self, package_dir: &Path) -> Result<Vec<PathBuf>> {
+        list_entries(package_dir, ARTIFACT_EXCLUDES)
+    }
+
+    fn dependency_dirs(&self) -> &'static [&'static str] {
+        &[VENDOR_DIR]
+    }
+}
+
+fn python_command() -> &'static str {
+    if cfg!(windows) {
+        "python"
+    } else {
+        "python3"
+    }
+}
+
+fn read_toml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| AetherError::invalid_project(format!("Invalid {}: {}", path.display(), e)))
+}
+
+/// The Python version to run, from `.python-version`, `runtime.txt`, the
+/// Pipfile or pyproject.toml, as `major.minor`.
+fn detect_python_version(
+    project_path: &Path,
+    pyproject: &PyProject,
+    pipfile: &Pipfile,
+) -> Result<String> {
+    let mut candidates = Vec::new();
+
+    for file in [".python-version", "runtime.txt"] {
+        let path = project_path.join(file);
+        if path.is_file() {
+            candidates.push(std::fs::read_to_string(path)?);
+        }
+    }
+    if let Some(requires) = &pipfile.requires {
+        candidates.extend(requires.python_version.clone());
+        candidates.extend(requires.python_full_version.clone());
+    }
+    if let Some(project) = &pyproject.project {
+        candidates.extend(project.requires_python.clone());
+    }
+    if let Some(poetry) = pyproject
+        .tool
+        .as_ref()
+        .and_then(|tool| tool.poetry.as_ref())
+    {
+        candidates.extend(
+            poetry
+                .dependencies
+                .get("python")
+                .and_then(|spec| spec.as_str())
+                .map(str::to_string),
+        );
+    }
+
+    Ok(candidates
+        .iter()
+        .find_map(|spec| major_minor(spec))
+        .unwrap_or_else(|| DEFAULT_PYTHON_VERSION.to_string()))
+}
+
+/// The first `major.minor` version in a version file or specifier, e.g.
+/// `3.11` for `python-3.11.4` or `>=3.11,<4`.
+fn major_minor(spec: &str) -> Option<String> {
+    let start = spec.find(|c: char| c.is_ascii_digit())?;
+    let version: String = spec[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let mut parts = version.split('.').filter(|part| !part.is_empty());
+    Some(format!("{}.{}", parts.next()?, parts.next()?))
+}
+
+/// The package name of a requirement line, ignoring comments and pip
+/// options.
+fn requirement_name(line: &str) -> Option<String> {
+    let line = line.split('#').next()?.trim();
+    if line.is_empty() || line.starts_with('-') {
+        return None;
+    }
+    let name: String = line
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .collect();
+    (!name.is_empty()).then(|| normalize(&name))
+}
+
+/// Package names compare case-insensitively with `-`, `_` and `.` equivalent.
+fn normalize(name: &str) -> String {
+    name.to_ascii_lowercase().replace(['_', '.'], "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_major_minor() {
+        assert_eq!(major_minor("3.11.4\n"), Some("3.11".to_string()));
+        assert_eq!(major_minor("python-3.10.2"), Some("3.10".to_string()));
+        assert_eq!(major_minor(">=3.9,<4"), Some("3.9".to_string()));
+        assert_eq!(major_minor("^3"), None);
+    }
+
+    #[test]
+    fn test_detect_requirements_project() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("requirements.txt"),
+            "# web\nFlask==3.0.0\ngunicorn>=21 ; sys_platform != 'win32'\n-r extra.txt\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("pyproject.toml"),
+            "[project]\nname = \"shop-api\"\nversion = \"0.3.0\"\nrequires-python = \">=3.11\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("app.py"), "").unwrap();
+
+        let runtime = PythonRuntime::detect(dir.path()).unwrap().unwrap();
+        assert_eq!(runtime.app_name(), "shop-api");
+        assert_eq!(runtime.version(), Some("0.3.0"));
+        assert_eq!(runtime.runtime_id(), "python:3.11");
+        assert_eq!(runtime.dependencies, ["flask", "gunicorn"]);
+        assert_eq!(
+            runtime.start_command().as_deref(),
+            Some("python -m gunicorn app:app")
+        );
+    }
+}
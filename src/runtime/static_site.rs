@@ -0,0 +1,312 @@
+use super::{directory_name, list_entries, NodeRuntime, PackageDir, PackageJson, Runtime};
+use crate::api::{CacheRule, StaticSiteConfig};
+use crate::builder::ProjectBuilder;
+use crate::project_config::{ProjectConfig, StaticConfig};
+use crate::{AetherError, Result};
+use futures::future::BoxFuture;
+use std::path::{Path, PathBuf};
+
+/// Entries left out of the artifact when the project directory itself is
+/// the site.
+const ARTIFACT_EXCLUDES: &[&str] = &[".git", ".aether", ".env", "node_modules", "aether.json"];
+
+/// Frontend tooling whose conventions decide the output directory and the
+/// default serving rules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framework {
+    Vite,
+    CreateReactApp,
+    NextExport,
+    /// Hand-written HTML, or a build configured entirely in aether.json
+    Plain,
+}
+
+impl Framework {
+    fn output_dir(self) -> Option<&'static str> {
+        match self {
+            Framework::Vite => Some("dist"),
+            Framework::CreateReactApp => Some("build"),
+            Framework::NextExport => Some("out"),
+            Framework::Plain => None,
+        }
+    }
+
+    /// Directory of content-hashed assets that can be cached forever.
+    fn hashed_assets(self) -> Option<&'static str> {
+        match self {
+            Framework::Vite => Some("/assets/*"),
+            Framework::CreateReactApp => Some("/static/*"),
+            Framework::NextExport => Some("/_next/static/*"),
+            Framework::Plain => None,
+        }
+    }
+
+    fn is_single_page_app(self) -> bool {
+        matches!(self, Framework::Vite | Framework::CreateReactApp)
+    }
+}
+
+/// Sites deployed as prebuilt files, optionally produced by a Node.js
+/// build.
+pub struct StaticRuntime {
+    project_path: PathBuf,
+    name: String,
+    node: Option<NodeRuntime>,
+    framework: Framework,
+    config: StaticConfig,
+}
+
+impl StaticRuntime {
+    /// Detect a frontend build (Vite, Create React App or a Next.js static
+    /// export) or a project configured with a `static` section in
+    /// aether.json.
+    pub fn detect(project_path: &Path) -> Result<Option<Self>> {
+        let node = NodeRuntime::detect(project_path)?;
+        let framework = node
+            .as_ref()
+            .and_then(|node| detect_framework(project_path, node.package_json()));
+
+        match (ProjectConfig::load(project_path)?.static_site, framework) {
+            (Some(config), framework) => Ok(Some(Self::with_config(
+                project_path,
+                node,
+                framework.unwrap_or(Framework::Plain),
+                config,
+            ))),
+            (None, Some(framework)) => Ok(Some(Self::with_config(
+                project_path,
+                node,
+                framework,
+                StaticConfig::default(),
+            ))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Detect a hand-written site with an index.html in the project root.
+    pub fn detect_plain(project_path: &Path) -> Result<Option<Self>> {
+        if !project_path.join("index.html").is_file() {
+            return Ok(None);
+        }
+        let node = NodeRuntime::detect(project_path)?;
+        Ok(Some(Self::with_config(
+            project_path,
+            node,
+            Framework::Plain,
+            StaticConfig::default(),
+        )))
+    }
+
+    fn with_config(
+        project_path: &Path,
+        node: Option<NodeRuntime>,
+        framework: Framework,
+        config: StaticConfig,
+    ) -> Self {
+        let name = node
+            .as_ref()
+            .map(|node| node.package_json().name.clone())
+            .unwrap_or_else(|| directory_name(project_path));
+
+        Self {
+            project_path: project_path.to_path_buf(),
+            name,
+            node,
+            framework,
+            config,
+        }
+    }
+
+    pub fn framework(&self) -> Framework {
+        self.framework
+    }
+
+    /// Directory holding the built site, relative to the project root. The
+    /// project root itself is the site when there is none.
+    pub fn output_dir(&self) -> Option<&str> {
+        self.config
+            .output_dir
+            .as_deref()
+            .or_else(|| self.framework.output_dir())
+    }
+}
+
+impl Runtime for StaticRuntime {
+    fn display_name(&self) -> &'static str {
+        "Static"
+    }
+
+    fn app_name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Option<&str> {
+        self.node
+            .as_ref()
+            .and_then(|node| node.package_json().version.as_deref())
+    }
+
+    fn runtime_id(&self) -> String {
+        "static".to_string()
+    }
+
+    fn start_command(&self) -> Option<String> {
+        None
+    }
+
+    fn source_root(&self) -> &Path {
+        &self.project_path
+    }
+
+    fn build<'a>(
+        &'a self,
+        builder: &'a ProjectBuilder,
+        root: &'a Path,
+    ) -> BoxFuture<'a, Result<PackageDir>> {
+        Box::pin(async move {
+            match &self.node {
+                // Only the output ships, so prebuilt sites need no install
+                Some(node) if !builder.skips_build() => {
+                    node.install_build_dependencies(builder, root).await?;
+                    builder
+                        .run_build_steps(root, node.build_script(root))
+                        .await?;
+                }
+                _ => builder.run_build_steps(root, None).await?,
+            }
+
+            let output_dir = match self.output_dir() {
+                Some(dir) => root.join(dir),
+                None => root.to_path_buf(),
+            };
+            if !output_dir.is_dir() {
+                return Err(AetherError::build(format!(
+                    "Build output directory '{}' not found; set static.output_dir in aether.json",
+                    self.output_dir().unwrap_or(".")
+                )));
+            }
+            if !output_dir.join("index.html").is_file() {
+                builder.output(&format!(
+                    "⚠️  No index.html in {}",
+                    self.output_dir().unwrap_or("the project root")
+                ));
+            }
+
+            builder.output(&format!(
+                "🌐 Packaging static site from {}",
+                self.output_dir().unwrap_or(".")
+            ));
+            Ok(PackageDir::new(output_dir))
+        })
+    }
+
+    fn artifact_paths(&self, package_dir: &Path) -> Result<Vec<PathBuf>> {
+        list_entries(package_dir, ARTIFACT_EXCLUDES)
+    }
+
+    fn static_site(&self) -> Option<StaticSiteConfig> {
+        let spa = self
+            .config
+            .spa
+            .unwrap_or(self.framework.is_single_page_app());
+        let cache = self
+            .config
+            .cache
+            .clone()
+            .unwrap_or_else(|| default_cache_rules(self.framework));
+
+        Some(StaticSiteConfig {
+            spa_fallback: spa.then(|| "index.html".to_string()),
+            cache,
+            redirects: self.config.redirects.clone(),
+        })
+    }
+}
+
+/// Recognize frontend tooling that produces a static build. Next.js
+/// projects only count when configured for `output: 'export'`; otherwise
+/// they run as a Node.js server.
+fn detect_framework(project_path: &Path, package_json: &PackageJson) -> Option<Framework> {
+    if package_json.depends_on("next") {
+        return is_next_static_export(project_path).then_some(Framework::NextExport);
+    }
+    if package_json.depends_on("react-scripts") {
+        return Some(Framework::CreateReactApp);
+    }
+
+    // Vite apps with a start script serve themselves (e.g. SSR)
+    let has_start_script = package_json
+        .scripts
+        .as_ref()
+        .is_some_and(|scripts| scripts.contains_key("start"));
+    if package_json.depends_on("vite") && !has_start_script {
+        return Some(Framework::Vite);
+    }
+
+    None
+}
+
+fn is_next_static_export(project_path: &Path) -> bool {
+    [
+        "next.config.js",
+        "next.config.mjs",
+        "next.config.cjs",
+        "next.config.ts",
+    ]
+    .iter()
+    .filter_map(|file| std::fs::read_to_string(project_path.join(file)).ok())
+    .any(|content| {
+        let compact: String = content.split_whitespace().collect();
+        compact.contains("output:'export'") || compact.contains("output:\"export\"")
+    })
+}
+
+/// Hashed assets are cached forever; HTML is revalidated so new deployments
+/// show up immediately.
+fn default_cache_rules(framework: Framework) -> Vec<CacheRule> {
+    let mut rules = Vec::new();
+    if let Some(assets) = framework.hashed_assets() {
+        rules.push(CacheRule {
+            source: assets.to_string(),
+            cache_control: "public, max-age=31536000, immutable".to_string(),
+        });
+    }
+    rules.push(CacheRule {
+        source: "/*.html".to_string(),
+        cache_control: "no-cache".to_string(),
+    });
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_json(json: &str) -> PackageJson {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_detect_framework() {
+        let dir = tempfile::tempdir().unwrap();
+        let vite = package_json(r#"{"name":"web","devDependencies":{"vite":"^5"}}"#);
+        let vite_ssr = package_json(
+            r#"{"name":"web","scripts":{"start":"node server.js"},"dependencies":{"vite":"^5"}}"#,
+        );
+        let next = package_json(r#"{"name":"web","dependencies":{"next":"14"}}"#);
+
+        assert_eq!(detect_framework(dir.path(), &vite), Some(Framework::Vite));
+        assert_eq!(detect_framework(dir.path(), &vite_ssr), None);
+        assert_eq!(detect_framework(dir.path(), &next), None);
+
+        std::fs::write(
+            dir.path().join("next.config.mjs"),
+            "export default {\n  output: 'export',\n};\n",
+        )
+        .unwrap();
+        assert_eq!(
+            detect_framework(dir.path(), &next),
+            Some(Framework::NextExport)
+        );
+    }
+}
@@ -1,52 +1,50 @@
-use crate::Result;
+use crate::{
+    api::{ArtifactDownload, CompressionFormat, DependencyLayerRef, StoredArtifact},
+    artifact_store::{self, ArtifactFormat, ArtifactStore, UploadedFile},
+    upload::{self, ChecksumHasher, Checksums, ProgressCallback},
+    Result,
+};
 use aws_config::{BehaviorVersion, Region};
 use aws_sdk_s3::{primitives::ByteStream, Client};
-use std::env;
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use http_body::Frame;
+use http_body_util::StreamBody;
 use std::path::Path;
+use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// How long download URLs of stored files stay valid.
+const DOWNLOAD_URL_SECS: u64 = 24 * 60 * 60;
+
+/// Uploads straight to an S3 bucket, e.g. on MinIO or Storj for
+/// self-hosted platforms. Artifacts are named by their SHA-256, so
+/// unchanged artifacts are reused.
 pub struct S3Uploader {
     pub client: Client,
     pub bucket_name: String,
-    output_callback: Option<Box<dyn Fn(&str) + Send + Sync>>,
+    progress: Option<ProgressCallback>,
 }
 
 impl S3Uploader {
-    pub fn with_output_callback<F>(mut self, callback: F) -> Self
-    where
-        F: Fn(&str) + Send + Sync + 'static,
-    {
-        self.output_callback = Some(Box::new(callback));
+    /// Report the bytes sent by each upload to `callback`.
+    pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
+        self.progress = Some(callback);
         self
     }
 
-    #[allow(dead_code)]
-    fn output(&self, message: &str) {
-        println!("{}", message);
-    }
-
-    pub async fn new() -> Result<Self> {
-        // Verify required AWS credentials are set
-        let _access_key = env::var("AWS_ACCESS_KEY_ID")
-            .map_err(|_| anyhow::anyhow!("AWS_ACCESS_KEY_ID must be set"))?;
-
-        let _secret_key = env::var("AWS_SECRET_ACCESS_KEY")
-            .map_err(|_| anyhow::anyhow!("AWS_SECRET_ACCESS_KEY must be set"))?;
-
-        // Get S3 config from environment variables
-        let bucket_name = env::var("AETHER_S3_BUCKET")
-            .map_err(|_| anyhow::anyhow!("AETHER_S3_BUCKET environment variable not set"))?;
-
-        let region = env::var("AETHER_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
-
-        let endpoint = env::var("AETHER_S3_ENDPOINT").ok(); // Optional custom endpoint
+    /// Connect to `bucket`, on the S3-compatible service at `endpoint` if
+    /// given. Credentials come from the usual AWS sources, such as
+    /// `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY` or `~/.aws/credentials`.
+    pub async fn new(bucket: &str, region: Option<&str>, endpoint: Option<&str>) -> Result<Self> {
+        let region = region.unwrap_or("us-east-1").to_string();
 
         // Create AWS config
         let mut config_loader =
             aws_config::defaults(BehaviorVersion::latest()).region(Region::new(region));
 
         // Set custom endpoint if provided (for Storj, MinIO, etc.)
-        if let Some(endpoint_url) = &endpoint {
+        if let Some(endpoint_url) = endpoint {
             config_loader = config_loader.endpoint_url(endpoint_url);
         }
 
@@ -56,135 +54,179 @@ impl S3Uploader {
         let mut s3_config_builder = aws_sdk_s3::config::Builder::from(&config);
 
         if endpoint.is_some() {
-            // Force path style and disable checksum for S3-compatible services like Storj
-            s3_config_builder = s3_config_builder.force_path_style(true);
+            // Force path style, and only send the checksums we compute
+            // ourselves: S3-compatible services like Storj reject the
+            // trailing checksums the SDK adds by default
+            s3_config_builder = s3_config_builder
+                .force_path_style(true)
+                .request_checksum_calculation(
+                    aws_sdk_s3::config::RequestChecksumCalculation::WhenRequired,
+                );
         }
 
         let s3_config = s3_config_builder.build();
         let client = Client::from_conf(s3_config);
 
-        Ok(Self {
+        let uploader = Self {
             client,
-            bucket_name,
-            output_callback: None,
-        })
+            bucket_name: bucket.to_string(),
+            progress: None,
+        };
+        uploader.test_bucket_access().await?;
+        Ok(uploader)
     }
 
-    pub async fn upload_artifact(
+    /// Upload `path`, whose content has `checksums`, to `key`, or with
+    /// `reuse_stored` skip the upload when the bucket already holds the key.
+    async fn upload_file(
         &self,
-        artifact_path: &Path,
-        app_id: Uuid,
-        version: &str,
-    ) -> Result<(String, String)> {
-        // Test S3 connection first
-        self.test_bucket_access().await?;
-
-        // Generate S3 key
-        let key = format!(
-            "artifacts/{}/{}/{}.tar.gz",
-            app_id,
-            version,
-            chrono::Utc::now().timestamp()
-        );
-
-        // Read file content as bytes (better compatibility with Storj)
-        let file_content = std::fs::read(artifact_path)
-            .map_err(|e| anyhow::anyhow!("Failed to read artifact file: {}", e))?;
-        let body = ByteStream::from(file_content);
-
-        // Upload to S3
-        println!("🔄 Starting upload...");
-        println!("   Key: {}", key);
-
-        let mut put_request = self
+        path: &Path,
+        checksums: Checksums,
+        key: &str,
+        content_type: &str,
+        (app_id, version): (Uuid, &str),
+        reuse_stored: bool,
+    ) -> Result<UploadedFile> {
+        let s3_url = format!("s3://{}/{}", self.bucket_name, key);
+        if reuse_stored && self.exists(key).await? {
+            return Ok(UploadedFile {
+                key: s3_url,
+                sha256: checksums.sha256_hex(),
+                reused: true,
+            });
+        }
+
+        // Stream the file in chunks rather than reading it into memory,
+        // hashing what is sent to check it against the checksums
+        let size = std::fs::metadata(path)?.len();
+        let hasher = Arc::new(Mutex::new(ChecksumHasher::default()));
+        let sink = hasher.clone();
+        let progress = self.progress.clone();
+        if let Some(progress) = &progress {
+            progress(0, size);
+        }
+        let mut sent = 0;
+        let stream = upload::file_range_stream(path, 0, size, move |chunk| {
+            sink.lock().unwrap().update(chunk);
+            if let Some(progress) = &progress {
+                sent += chunk.len() as u64;
+                progress(sent, size);
+            }
+        })
+        .await?;
+        let body = ByteStream::from_body_1_x(StreamBody::new(stream.map_ok(Frame::data)));
+
+        let output = self
             .client
             .put_object()
             .bucket(&self.bucket_name)
-            .key(&key)
+            .key(key)
             .body(body)
-            .content_type("application/gzip")
+            .content_length(size as i64)
+            .content_type(content_type)
+            .content_md5(checksums.md5_base64())
+            .checksum_sha256(checksums.sha256_base64())
             .metadata("app_id", app_id.to_string())
             .metadata("version", version)
-            .metadata("uploaded_at", chrono::Utc::now().to_rfc3339());
-
-        // For Storj/S3-compatible services, disable content SHA256
-        if env::var("AETHER_S3_ENDPOINT").is_ok() {
-            put_request =
-                put_request.checksum_algorithm(aws_sdk_s3::types::ChecksumAlgorithm::Sha256);
+            .metadata("uploaded_at", chrono::Utc::now().to_rfc3339())
+            .send()
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to upload {} to bucket '{}': {}",
+                    key,
+                    self.bucket_name,
+                    aws_sdk_s3::error::DisplayErrorContext(&e)
+                )
+            })?;
+
+        let streamed = std::mem::take(&mut *hasher.lock().unwrap()).finish();
+        if streamed != checksums {
+            return Err(anyhow::anyhow!(
+                "{} changed while it was being uploaded (SHA-256 {} before, {} sent)",
+                path.display(),
+                checksums.sha256_hex(),
+                streamed.sha256_hex()
+            )
+            .into());
         }
-
-        let result = put_request.send().await;
-
-        match result {
-            Ok(_) => {
-                // Don't print here - let the caller handle progress/success messages
-            }
-            Err(e) => {
-                eprintln!("❌ Upload failed: {:?}", e);
+        if let Some(stored) = output.checksum_sha256() {
+            if stored != checksums.sha256_base64() {
                 return Err(anyhow::anyhow!(
-                    "Failed to upload to S3: {}\nBucket: {}\nKey: {}\nError: {:?}",
-                    e,
-                    self.bucket_name,
-                    key,
-                    e
+                    "Storage reports SHA-256 {} for {} but {} was sent",
+                    stored,
+                    path.display(),
+                    checksums.sha256_base64()
                 )
                 .into());
             }
         }
 
-        // Generate presigned URL (valid for 24 hours)
-        let presigned_url = self.get_presigned_url(&key, 86400).await?;
+        Ok(UploadedFile {
+            key: s3_url,
+            sha256: checksums.sha256_hex(),
+            reused: false,
+        })
+    }
 
-        // Return S3 URL and presigned URL
-        let s3_url = format!("s3://{}/{}", self.bucket_name, key);
-        Ok((s3_url, presigned_url))
+    /// Whether the bucket holds `key`.
+    async fn exists(&self, key: &str) -> Result<bool> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(false),
+            Err(e) => Err(anyhow::anyhow!(
+                "Failed to look up {} in bucket '{}': {}",
+                key,
+                self.bucket_name,
+                aws_sdk_s3::error::DisplayErrorContext(&e)
+            )
+            .into()),
+        }
     }
 
     async fn test_bucket_access(&self) -> Result<()> {
-        println!("🔍 Testing S3 bucket access...");
-        println!("   Bucket: {}", self.bucket_name);
-
-        // Try to head bucket to test access
-        let result = self
+        let head = self
             .client
             .head_bucket()
             .bucket(&self.bucket_name)
             .send()
             .await;
+        if head.is_ok() {
+            return Ok(());
+        }
 
-        match result {
-            Ok(_) => {
-                println!("✅ S3 bucket access successful");
-                Ok(())
-            }
-            Err(e) => {
-                println!("❌ S3 bucket access failed: {}", e);
-
-                // Try to list all buckets to test general AWS access
-                println!("🔍 Testing general AWS S3 access...");
-                let list_result = self.client.list_buckets().send().await;
-                match list_result {
-                    Ok(buckets) => {
-                        println!("✅ AWS S3 access working. Available buckets:");
-                        let bucket_list = buckets.buckets();
-                        for bucket in bucket_list {
-                            if let Some(name) = bucket.name() {
-                                println!("   - {}", name);
-                            }
-                        }
-                        Err(anyhow::anyhow!(
-                            "Bucket '{}' not found or no access. See available buckets above.",
-                            self.bucket_name
-                        )
-                        .into())
+        // List the buckets the credentials can see, to tell a wrong bucket
+        // name from missing access
+        match self.client.list_buckets().send().await {
+            Ok(buckets) => {
+                let names: Vec<_> = buckets
+                    .buckets()
+                    .iter()
+                    .filter_map(|bucket| bucket.name())
+                    .collect();
+                Err(anyhow::anyhow!(
+                    "Bucket '{}' not found or no access. Available buckets: {}",
+                    self.bucket_name,
+                    if names.is_empty() {
+                        "none".to_string()
+                    } else {
+                        names.join(", ")
                     }
-                    Err(list_err) => Err(anyhow::anyhow!(
-                        "Cannot access AWS S3 at all: {}\nCheck your credentials and permissions",
-                        list_err
-                    )
-                    .into()),
-                }
+                )
+                .into())
             }
+            Err(list_err) => Err(anyhow::anyhow!(
+                "Cannot access S3 at all: {}\nCheck your credentials and permissions",
+                aws_sdk_s3::error::DisplayErrorContext(&list_err)
+            )
+            .into()),
         }
     }
 
@@ -206,3 +248,197 @@ impl S3Uploader {
         Ok(presigned_request.uri().to_string())
     }
 }
+
+impl ArtifactStore for S3Uploader {
+    fn describe(&self) -> String {
+        format!("S3 bucket '{}'", self.bucket_name)
+    }
+
+    fn upload_artifact<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        format: ArtifactFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let checksums = Checksums::of_file(path).await?;
+            let key = format!(
+                "artifacts/{}/{}.{}",
+                app_id,
+                checksums.sha256_hex(),
+                format.extension()
+            );
+            self.upload_file(
+                path,
+                checksums,
+                &key,
+                format.content_type(),
+                (app_id, version),
+                true,
+            )
+            .await
+        })
+    }
+
+    fn find_dependency_layer<'a>(
+        &'a self,
+        app_id: Uuid,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<Option<DependencyLayerRef>>> {
+        Box::pin(async move {
+            let key = artifact_store::dependency_layer_key(app_id, layer_key, compression);
+            Ok(self.exists(&key).await?.then(|| DependencyLayerRef {
+                key: layer_key.to_string(),
+                artifact_url: format!("s3://{}/{}", self.bucket_name, key),
+                sha256: None,
+            }))
+        })
+    }
+
+    fn upload_dependency_layer<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+        layer_key: &'a str,
+        compression: CompressionFormat,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let key = artifact_store::dependency_layer_key(app_id, layer_key, compression);
+            let checksums = Checksums::of_file(path).await?;
+            self.upload_file(
+                path,
+                checksums,
+                &key,
+                compression.content_type(),
+                (app_id, version),
+                false,
+            )
+            .await
+        })
+    }
+
+    fn upload_build_log<'a>(
+        &'a self,
+        path: &'a Path,
+        app_id: Uuid,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<UploadedFile>> {
+        Box::pin(async move {
+            let key = format!("artifacts/{}/{}/build.log", app_id, version);
+            let checksums = Checksums::of_file(path).await?;
+            self.upload_file(
+                path,
+                checksums,
+                &key,
+                "text/plain",
+                (app_id, version),
+                false,
+            )
+            .await
+        })
+    }
+
+    fn download_url<'a>(
+        &'a self,
+        _app_id: Uuid,
+        key: &'a str,
+    ) -> BoxFuture<'a, Result<Option<ArtifactDownload>>> {
+        Box::pin(async move {
+            let prefix = format!("s3://{}/", self.bucket_name);
+            let Some(object_key) = key.strip_prefix(&prefix) else {
+                return Ok(None);
+            };
+            Ok(Some(ArtifactDownload {
+                download_url: self
+                    .get_presigned_url(object_key, DOWNLOAD_URL_SECS)
+                    .await?,
+                expires_in: DOWNLOAD_URL_SECS,
+                sha256: None,
+            }))
+        })
+    }
+
+    fn list_artifacts(&self, app_id: Uuid) -> BoxFuture<'_, Result<Vec<StoredArtifact>>> {
+        Box::pin(async move {
+            // Artifacts sit directly under the app's prefix, named
+            // `<sha256>.<ext>`; the delimiter leaves out layers and logs
+            let prefix = format!("artifacts/{}/", app_id);
+            let mut artifacts = Vec::new();
+            let mut continuation_token = None;
+            loop {
+                let page = self
+                    .client
+                    .list_objects_v2()
+                    .bucket(&self.bucket_name)
+                    .prefix(&prefix)
+                    .delimiter("/")
+                    .set_continuation_token(continuation_token)
+                    .send()
+                    .await
+                    .map_err(|e| {
+                        anyhow::anyhow!(
+                            "Failed to list artifacts in bucket '{}': {}",
+                            self.bucket_name,
+                            aws_sdk_s3::error::DisplayErrorContext(&e)
+                        )
+                    })?;
+                for object in page.contents() {
+                    let Some(key) = object.key() else {
+                        continue;
+                    };
+                    let Some((sha256, _)) = key[prefix.len()..].split_once('.') else {
+                        continue;
+                    };
+                    artifacts.push(StoredArtifact {
+                        artifact_url: format!("s3://{}/{}", self.bucket_name, key),
+                        sha256: sha256.to_string(),
+                        size: object.size().unwrap_or_default().max(0) as u64,
+                        version: None,
+                        created_at: object.last_modified().and_then(|modified| {
+                            chrono::DateTime::from_timestamp(
+                                modified.secs(),
+                                modified.subsec_nanos(),
+                            )
+                        }),
+                    });
+                }
+                continuation_token = page.next_continuation_token().map(str::to_string);
+                if continuation_token.is_none() {
+                    return Ok(artifacts);
+                }
+            }
+        })
+    }
+
+    fn delete_artifact<'a>(
+        &'a self,
+        _app_id: Uuid,
+        artifact: &'a StoredArtifact,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let prefix = format!("s3://{}/", self.bucket_name);
+            let key = artifact
+                .artifact_url
+                .strip_prefix(&prefix)
+                .unwrap_or(&artifact.artifact_url);
+            self.client
+                .delete_object()
+                .bucket(&self.bucket_name)
+                .key(key)
+                .send()
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to delete {} from bucket '{}': {}",
+                        key,
+                        self.bucket_name,
+                        aws_sdk_s3::error::DisplayErrorContext(&e)
+                    )
+                })?;
+            Ok(())
+        })
+    }
+}
@@ -1,8 +1,6 @@
 use crate::pokemon_theme::{PokemonLoader, PokemonTheme, PokemonType};
 use crate::pokemon_widgets::{BattleAnimation, PokemonNotification, PokemonStatus};
-use crate::{api::ApiClient, builder::ProjectBuilder, config::Config, Result};
-
-use tar::Builder as TarBuilder;
+use crate::{api::ApiClient, config::Config, Result};
 
 use chrono;
 use crossterm::{
@@ -74,8 +72,19 @@ pub struct TerminalApp {
     // Output buffering for better log organization
     output_buffer: Vec<String>,
     is_command_running: bool,
+    // Output of a deploy running in the background
+    deploy_output: Option<tokio::sync::mpsc::UnboundedReceiver<DeployUpdate>>,
+}
+
+/// Output sent from a background deploy to the terminal tab.
+enum DeployUpdate {
+    Line(String),
+    /// Bytes of the artifact uploaded so far
+    Upload { sent: u64, total: u64 },
 }
 
+type DeployOutput = tokio::sync::mpsc::UnboundedSender<DeployUpdate>;
+
 #[derive(Clone)]
 struct FileTreeItem {
     name: String,
@@ -130,6 +139,7 @@ impl TerminalApp {
             sparkle_positions: Vec::new(),
             output_buffer: Vec::new(),
             is_command_running: false,
+            deploy_output: None,
         };
 
         // Build initial file tree
@@ -956,15 +966,30 @@ impl TerminalApp {
 
                 self.add_output_line("🚀 Starting deployment...".to_string());
 
-                // Use built-in deploy functionality instead of external command
-                match self.deploy_current_project().await {
-                    Ok(_) => {
-                        self.add_output_line("✅ Deployment completed successfully!".to_string());
-                    }
-                    Err(e) => {
-                        self.add_output_line(format!("❌ Deployment failed: {}", e));
-                    }
+                if self.deploy_output.is_some() {
+                    self.add_output_line("⏳ A deployment is already running".to_string());
+                    return Ok(());
                 }
+
+                // Use built-in deploy functionality instead of external command,
+                // in the background so its output shows up while it runs
+                let (output, receiver) = tokio::sync::mpsc::unbounded_channel();
+                self.deploy_output = Some(receiver);
+                let client = self.client.clone();
+                let project_path = self.current_dir.clone();
+                tokio::spawn(async move {
+                    match Self::deploy_current_project(client, project_path, output.clone()).await {
+                        Ok(_) => {
+                            let _ = output.send(DeployUpdate::Line(
+                                "✅ Deployment completed successfully!".to_string(),
+                            ));
+                        }
+                        Err(e) => {
+                            let _ = output
+                                .send(DeployUpdate::Line(format!("❌ Deployment failed: {}", e)));
+                        }
+                    }
+                });
             }
             "logs" => {
                 let (app_name, follow) = if args.len() == 1
@@ -1754,6 +1779,7 @@ async fn run_app<B: ratatui::backend::Backend>(
     app: &mut TerminalApp,
 ) -> Result<()> {
     loop {
+        app.drain_deploy_output();
         terminal.draw(|f| ui(f, app))?;
 
         // Update applications list if authenticated and on apps tab
@@ -2533,7 +2559,7 @@ fn render_apps_tab(f: &mut Frame, app: &TerminalApp, area: Rect) {
     let details_text = if !app.is_authenticated {
         "🔐 Authentication Required\n\n❌ Status: NOT AUTHENTICATED\n\n🔧 Actions needed:\n• Run 'aether login' to authenticate\n• Then return to view your applications\n\n💡 Commands:\n  aether register  - Create account\n  aether login     - Login to account".to_string()
     } else if app.applications.is_empty() {
-        "📦 No Applications Yet\n\n✨ Ready to deploy your first app!\n\n🚀 Quick Start:\n1. Navigate to your project folder\n2. Run 'aether deploy'\n3. Watch your app come to life!\n\n💡 Supported runtimes:\n• Node.js (package.json)\n• More coming soon...".to_string()
+        "📦 No Applications Yet\n\n✨ Ready to deploy your first app!\n\n🚀 Quick Start:\n1. Navigate to your project folder\n2. Run 'aether deploy'\n3. Watch your app come to life!\n\n💡 Supported runtimes:\n• Node.js (package.json)\n• Python (pyproject.toml, requirements.txt, Pipfile)\n• Deno (deno.json)\n• Static sites (Vite, CRA, Next export, index.html)\n• More coming soon...".to_string()
     } else {
         let selected_app = if app.selected_app_index < app.applications.len() {
             Some(&app.applications[app.selected_app_index])
@@ -2647,206 +2673,213 @@ fn render_auth_tab(f: &mut Frame, app: &TerminalApp, area: Rect) {
 }
 
 impl TerminalApp {
-    /// Deploy the current project using API directly instead of external command
-    async fn deploy_current_project(&mut self) -> Result<()> {
+    /// Show the output of a deploy running in the background as it arrives.
+    fn drain_deploy_output(&mut self) {
+        let Some(receiver) = self.deploy_output.as_mut() else {
+            return;
+        };
+        let mut updates = Vec::new();
+        let finished = loop {
+            match receiver.try_recv() {
+                Ok(update) => updates.push(update),
+                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break false,
+                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => break true,
+            }
+        };
+        for update in updates {
+            match update {
+                DeployUpdate::Line(line) => self.add_output_line(line),
+                DeployUpdate::Upload { sent, total } => {
+                    let percent = (sent * 100).checked_div(total).unwrap_or(100);
+                    let line = format!(
+                        "📤 Uploaded {} / {} ({}%)",
+                        crate::utils::format_size(sent),
+                        crate::utils::format_size(total),
+                        percent
+                    );
+                    // Update the progress line in place while the upload runs
+                    match self.output_lines.last_mut() {
+                        Some(last) if last.starts_with("📤 Uploaded ") => *last = line,
+                        _ => self.add_output_line(line),
+                    }
+                }
+            }
+        }
+        if finished {
+            self.deploy_output = None;
+        }
+    }
+
+    /// Deploy the current project using API directly instead of external command,
+    /// sending progress to `output` as it happens
+    async fn deploy_current_project(
+        client: ApiClient,
+        project_path: std::path::PathBuf,
+        output: DeployOutput,
+    ) -> Result<()> {
         use crate::{
-            api::CreateAppRequest, builder::ProjectBuilder, commands::find_app_by_name,
-            presigned_uploader::PresignedUploader,
+            api::{CreateAppRequest, DeployRequest},
+            builder::ProjectBuilder,
+            commands::find_app_by_name,
         };
 
-        let project_path = self.current_dir.clone();
+        let say = |line: String| {
+            let _ = output.send(DeployUpdate::Line(line));
+        };
 
         // Step 1: Project Analysis
-        self.add_output_line("".to_string());
-        self.add_output_line("🔍 Analyzing project...".to_string());
-        self.add_output_line(format!("📁 Project path: {}", project_path.display()));
+        say("".to_string());
+        say("🔍 Analyzing project...".to_string());
+        say(format!("📁 Project path: {}", project_path.display()));
 
         // Initialize project builder
+        let config = crate::config::Config::load().unwrap_or_default();
         let builder = ProjectBuilder::new(&project_path)?;
         let app_name = builder.get_app_name();
         let app_runtime = builder.detect_runtime();
 
-        self.add_output_line(format!("📦 App name: {}", app_name));
-        self.add_output_line(format!("🏷️ Version: {}", builder.get_version()));
-        self.add_output_line(format!("🔧 Runtime: {}", app_runtime));
-        self.add_output_line("".to_string());
+        say(format!("📦 App name: {}", app_name));
+        say(format!("🏷️ Version: {}", builder.get_version()));
+        say(format!("🔧 Runtime: {}", app_runtime));
+        say("".to_string());
 
         // Check if app already exists
-        let existing_app = find_app_by_name(&self.client, &app_name).await?;
+        let existing_app = find_app_by_name(&client, &app_name).await?;
 
         let app = if let Some(existing_app) = existing_app {
-            self.add_output_line(format!("� Using existing application: {}", app_name));
+            say(format!("� Using existing application: {}", app_name));
             existing_app
         } else {
             // Create new application
-            self.add_output_line("� Creating new application...".to_string());
+            say("� Creating new application...".to_string());
             let create_request = CreateAppRequest {
                 name: app_name.to_string(),
-                description: Some(
-                    "NodeJS application deployed via AetherEngine CLI Dashboard".to_string(),
-                ),
+                description: Some(format!(
+                    "{} application deployed via AetherEngine CLI Dashboard",
+                    builder.runtime().display_name()
+                )),
                 runtime: app_runtime.clone(),
+                start_command: builder.start_command(),
+                parent_app_id: None,
+                preview_branch: None,
             };
 
-            self.client.create_application(create_request).await?
+            client.create_application(create_request).await?
         };
 
-        // Build the project
-        self.add_output_line("� Building project...".to_string());
-        let artifact_path = self.build_project_silent(&builder).await?;
-        self.add_output_line("🗜️ Creating deployment artifact...".to_string());
-        self.add_output_line(format!("📦 Artifact: {}", artifact_path.display()));
-        self.add_output_line("".to_string());
+        // Build the project, streaming build tool output to the terminal tab
+        say("� Building project...".to_string());
+        let sink = output.clone();
+        let builder = builder
+            .with_build_timeout(Duration::from_secs(config.build_timeout))
+            .with_output_callback(move |line| {
+                let _ = sink.send(DeployUpdate::Line(line.to_string()));
+            });
+        let artifact_path = builder.build(None).await?;
+        say(format!("📦 Artifact: {}", artifact_path.display()));
+        say("".to_string());
 
         // Step 4: Upload to S3
-        self.add_output_line("☁️ Preparing S3 upload...".to_string());
-        self.add_output_line("📤 Uploading artifact to S3...".to_string());
-        let (artifact_url, _presigned_url) = self
-            .upload_to_s3_silent(&artifact_path, app.id, &builder.get_version())
-            .await?;
+        say("☁️ Preparing S3 upload...".to_string());
+        say("📤 Uploading artifact to S3...".to_string());
+        let artifact = Self::upload_to_s3_silent(
+            &client,
+            &config,
+            &artifact_path,
+            app.id,
+            &builder.get_version(),
+            &output,
+        )
+        .await?;
 
-        self.add_output_line("✅ Upload successful!".to_string());
-        self.add_output_line("".to_string());
+        say("✅ Upload successful!".to_string());
+        say("".to_string());
 
         // Step 5: Create Deployment
-        self.add_output_line("🚀 Initiating deployment...".to_string());
-        let deployment = self
-            .client
-            .deploy_application(app.id, builder.get_version(), artifact_url.clone())
+        say("🚀 Initiating deployment...".to_string());
+        let deployment = client
+            .deploy_application(DeployRequest {
+                app_id: app.id,
+                version: builder.get_version(),
+                artifact_url: artifact.key.clone(),
+                artifact_sha256: Some(artifact.sha256.clone()),
+                start_command: builder.start_command(),
+                static_site: builder.runtime().static_site(),
+                ..Default::default()
+            })
             .await?;
 
-        self.add_output_line("🎉 Deployment completed successfully!".to_string());
-        self.add_output_line(format!("📱 App ID: {}", app.id));
-        self.add_output_line(format!("� Deployment ID: {}", deployment.id));
-        self.add_output_line("".to_string());
+        say("🎉 Deployment completed successfully!".to_string());
+        say(format!("📱 App ID: {}", app.id));
+        say(format!("� Deployment ID: {}", deployment.id));
+        say("".to_string());
         
         // Web Dashboard promotion
-        self.add_output_line("╔═══════════════════════════════════════════════════════════════════════════╗".to_string());
-        self.add_output_line("║                    🌐  MANAGE YOUR APP ONLINE  🌐                        ║".to_string());
-        self.add_output_line("║                                                                           ║".to_string());
-        self.add_output_line("║  🎯 View, monitor and manage your deployed app at:                       ║".to_string());
-        self.add_output_line("║                                                                           ║".to_string());
-        self.add_output_line("║                    ➡️  https://aetherngine.com/  ⬅️                        ║".to_string());
-        self.add_output_line("║                                                                           ║".to_string());
-        self.add_output_line("║  ✨ Real-time monitoring, logs, metrics & deployment management!         ║".to_string());
-        self.add_output_line("╚═══════════════════════════════════════════════════════════════════════════╝".to_string());
-
-        Ok(())
-    }
-
-    // Silent build method that doesn't interfere with dashboard output
-    async fn build_project_silent(&self, builder: &ProjectBuilder) -> Result<std::path::PathBuf> {
-        use std::process::Stdio;
-
-        // Check if dependencies need to be installed
-        let node_modules = builder.get_project_path().join("node_modules");
-        if !node_modules.exists() {
-            // Run npm install with suppressed output
-            let output = Command::new("npm")
-                .args(&["install"])
-                .current_dir(builder.get_project_path())
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .output()
-                .map_err(|e| anyhow::anyhow!("Failed to run npm install: {}", e))?;
-
-            if !output.status.success() {
-                return Err(anyhow::anyhow!("npm install failed").into());
-            }
-        }
-
-        // Run build script if it exists
-        if let Some(ref scripts) = builder.get_package_json().scripts {
-            if scripts.contains_key("build") {
-                let output = Command::new("npm")
-                    .args(&["run", "build"])
-                    .current_dir(builder.get_project_path())
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null())
-                    .output()
-                    .map_err(|e| anyhow::anyhow!("Failed to run build script: {}", e))?;
-
-                if !output.status.success() {
-                    return Err(anyhow::anyhow!("Build script failed").into());
-                }
-            }
-        }
-
-        // Create artifact
-        self.create_artifact_silent(builder).await
-    }
-
-    async fn create_artifact_silent(&self, builder: &ProjectBuilder) -> Result<std::path::PathBuf> {
-        use flate2::{write::GzEncoder, Compression};
-        use std::fs::File;
-        use tar::Builder as TarBuilder;
-
-        let temp_dir = std::env::temp_dir();
-        let artifact_path = temp_dir.join(format!("{}.tar.gz", builder.get_app_name()));
-
-        let tar_gz = File::create(&artifact_path)?;
-        let enc = GzEncoder::new(tar_gz, Compression::default());
-        let mut tar = TarBuilder::new(enc);
+        say("╔═══════════════════════════════════════════════════════════════════════════╗".to_string());
+        say("║                    🌐  MANAGE YOUR APP ONLINE  🌐                        ║".to_string());
+        say("║                                                                           ║".to_string());
+        say("║  🎯 View, monitor and manage your deployed app at:                       ║".to_string());
+        say("║                                                                           ║".to_string());
+        say("║                    ➡️  https://aetherngine.com/  ⬅️                        ║".to_string());
+        say("║                                                                           ║".to_string());
+        say("║  ✨ Real-time monitoring, logs, metrics & deployment management!         ║".to_string());
+        say("╚═══════════════════════════════════════════════════════════════════════════╝".to_string());
 
-        // Add files to tar
-        self.add_directory_to_tar(&mut tar, builder.get_project_path(), "")?;
-
-        tar.finish()?;
-        Ok(artifact_path)
-    }
-
-    fn add_directory_to_tar<W: std::io::Write>(
-        &self,
-        tar: &mut TarBuilder<W>,
-        dir: &std::path::Path,
-        prefix: &str,
-    ) -> Result<()> {
-        for entry in std::fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            let name = entry.file_name();
-            let name_str = name.to_string_lossy();
-
-            // Skip certain directories and files
-            if name_str.starts_with('.')
-                || name_str == "node_modules"
-                || name_str == "target"
-                || name_str == "dist"
-                || name_str.ends_with(".log")
-            {
-                continue;
-            }
-
-            let archive_path = if prefix.is_empty() {
-                name_str.to_string()
-            } else {
-                format!("{}/{}", prefix, name_str)
-            };
-
-            if path.is_dir() {
-                self.add_directory_to_tar(tar, &path, &archive_path)?;
-            } else {
-                tar.append_path_with_name(&path, &archive_path)?;
-            }
-        }
         Ok(())
     }
 
-    // Silent S3 upload that doesn't interfere with dashboard output
+    // Silent S3 upload that doesn't interfere with dashboard output. Progress
+    // is sent to `output` as every chunk of the artifact goes out.
     async fn upload_to_s3_silent(
-        &self,
+        client: &ApiClient,
+        config: &crate::config::Config,
         artifact_path: &std::path::Path,
         app_id: uuid::Uuid,
         version: &str,
-    ) -> Result<(String, String)> {
-        use crate::presigned_uploader::PresignedUploader;
-
-        let presigned_uploader = PresignedUploader::new(self.client.clone());
-        let (artifact_url, download_url) = presigned_uploader
-            .upload_artifact(artifact_path, app_id, version)
+        output: &DeployOutput,
+    ) -> Result<crate::artifact_store::UploadedFile> {
+        use crate::artifact_store::{self, ArtifactFormat};
+
+        let sink = output.clone();
+        let output_sink = output.clone();
+        let started = std::time::Instant::now();
+        let store = artifact_store::open(
+            config,
+            client.clone(),
+            Some(std::sync::Arc::new(move |sent, total| {
+                let _ = sink.send(DeployUpdate::Upload { sent, total });
+            })),
+            Some(Box::new(move |line| {
+                let _ = output_sink.send(DeployUpdate::Line(line.to_string()));
+            })),
+        )
+        .await?;
+        let artifact = store
+            .upload_artifact(
+                artifact_path,
+                app_id,
+                version,
+                ArtifactFormat {
+                    artifact_type: crate::api::ArtifactType::Tarball,
+                    compression: crate::api::CompressionFormat::Gzip,
+                },
+            )
             .await?;
 
-        Ok((artifact_url, download_url))
+        let size = std::fs::metadata(artifact_path)?.len();
+        if artifact.reused {
+            let _ = output.send(DeployUpdate::Line(crate::upload::reused_message(size)));
+        } else {
+            let elapsed = started.elapsed().as_secs_f64().max(0.001);
+            let _ = output.send(DeployUpdate::Line(format!(
+                "⚡ {} in {:.1}s ({}/s)",
+                crate::utils::format_size(size),
+                elapsed,
+                crate::utils::format_size((size as f64 / elapsed) as u64)
+            )));
+        }
+
+        Ok(artifact)
     }
 }
 
@@ -0,0 +1,409 @@
+use crate::{config::Config, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+/// Size of the chunks files are read and sent in, which bounds the memory
+/// an upload needs regardless of the file size.
+pub const CHUNK_SIZE: usize = 256 * 1024;
+
+/// Called with the number of bytes sent so far and the total size of the
+/// upload.
+pub type ProgressCallback = Arc<dyn Fn(u64, u64) + Send + Sync>;
+
+/// Called with each message an upload prints.
+pub type OutputCallback = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Open `path` as a stream of chunks, reporting progress to `progress` as
+/// chunks are handed to the HTTP client. Returns the file size alongside
+/// the stream.
+pub async fn file_stream(
+    path: &Path,
+    progress: Option<ProgressCallback>,
+) -> Result<(
+    u64,
+    impl Stream<Item = std::io::Result<Bytes>> + Send + Sync + 'static,
+)> {
+    let total = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+        .len();
+
+    if let Some(progress) = &progress {
+        progress(0, total);
+    }
+    let mut sent = 0;
+    let stream = file_range_stream(path, 0, total, move |chunk| {
+        if let Some(progress) = &progress {
+            sent += chunk.len() as u64;
+            progress(sent, total);
+        }
+    })
+    .await?;
+
+    Ok((total, stream))
+}
+
+/// Stream the `len` bytes of `path` starting at `offset`, calling
+/// `on_chunk` with every chunk read.
+pub async fn file_range_stream(
+    path: &Path,
+    offset: u64,
+    len: u64,
+    mut on_chunk: impl FnMut(&[u8]) + Send + Sync + 'static,
+) -> Result<impl Stream<Item = std::io::Result<Bytes>> + Send + Sync + 'static> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+    file.seek(SeekFrom::Start(offset)).await?;
+
+    Ok(
+        ReaderStream::with_capacity(file.take(len), CHUNK_SIZE).inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                on_chunk(chunk);
+            }
+        }),
+    )
+}
+
+/// Download `url` to `path`, streaming it to disk in chunks and reporting
+/// progress to `progress`. The file only appears at `path` once the
+/// download is complete and, if given, matches `expected_sha256`. Returns
+/// the checksums of the downloaded bytes.
+pub async fn download_file(
+    url: &str,
+    path: &Path,
+    expected_sha256: Option<&str>,
+    progress: Option<ProgressCallback>,
+) -> Result<Checksums> {
+    let response = reqwest::get(url).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(
+            anyhow::anyhow!("Download failed with status {}: {}", status, error_text).into(),
+        );
+    }
+    let total = response.content_length().unwrap_or(0);
+
+    let mut partial_name = path.as_os_str().to_owned();
+    partial_name.push(".part");
+    let partial_path = PathBuf::from(partial_name);
+    let mut file = tokio::fs::File::create(&partial_path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", partial_path.display(), e))?;
+    let mut hasher = ChecksumHasher::default();
+    let mut received = 0;
+    let mut chunks = response.bytes_stream();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial_path).await;
+                return Err(e.into());
+            }
+        };
+        hasher.update(&chunk);
+        file.write_all(&chunk).await?;
+        received += chunk.len() as u64;
+        if let Some(progress) = &progress {
+            progress(received, total.max(received));
+        }
+    }
+    file.flush().await?;
+
+    let checksums = hasher.finish();
+    let sha256 = checksums.sha256_hex();
+    if let Some(expected) = expected_sha256.filter(|expected| *expected != sha256) {
+        let _ = tokio::fs::remove_file(&partial_path).await;
+        return Err(anyhow::anyhow!(
+            "Downloaded artifact has SHA-256 {} but {} was stored: it was corrupted on the way",
+            sha256,
+            expected
+        )
+        .into());
+    }
+    tokio::fs::rename(&partial_path, path).await?;
+
+    Ok(checksums)
+}
+
+/// SHA-256 and MD5 digests of uploaded bytes. They are sent with the
+/// upload so storage rejects bytes corrupted on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksums {
+    pub sha256: [u8; 32],
+    pub md5: [u8; 16],
+}
+
+impl Checksums {
+    /// Checksums of a whole file.
+    pub async fn of_file(path: &Path) -> Result<Self> {
+        let (checksums, _) = Self::of_parts(path, u64::MAX).await?;
+        Ok(checksums)
+    }
+
+    /// Checksums of a whole file and of each of its `part_size` parts,
+    /// computed in a single read.
+    pub async fn of_parts(path: &Path, part_size: u64) -> Result<(Self, Vec<Self>)> {
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let mut whole = ChecksumHasher::default();
+        let mut part = ChecksumHasher::default();
+        let mut part_len = 0;
+        let mut parts = Vec::new();
+        loop {
+            let max_read = (part_size - part_len).min(buffer.len() as u64) as usize;
+            let read = file.read(&mut buffer[..max_read]).await?;
+            if read == 0 {
+                break;
+            }
+            whole.update(&buffer[..read]);
+            part.update(&buffer[..read]);
+            part_len += read as u64;
+            if part_len == part_size {
+                parts.push(std::mem::take(&mut part).finish());
+                part_len = 0;
+            }
+        }
+        if part_len > 0 {
+            parts.push(part.finish());
+        }
+        Ok((whole.finish(), parts))
+    }
+
+    pub fn sha256_hex(&self) -> String {
+        self.sha256
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// SHA-256 in base64, as the `x-amz-checksum-sha256` header takes it.
+    pub fn sha256_base64(&self) -> String {
+        BASE64.encode(self.sha256)
+    }
+
+    /// MD5 in base64, as the `Content-MD5` header takes it.
+    pub fn md5_base64(&self) -> String {
+        BASE64.encode(self.md5)
+    }
+}
+
+/// Computes `Checksums` incrementally, e.g. over the chunks of a stream.
+#[derive(Clone, Default)]
+pub struct ChecksumHasher {
+    sha256: Sha256,
+    md5: Md5,
+}
+
+impl ChecksumHasher {
+    pub fn update(&mut self, data: &[u8]) {
+        sha2::Digest::update(&mut self.sha256, data);
+        md5::Digest::update(&mut self.md5, data);
+    }
+
+    pub fn finish(self) -> Checksums {
+        Checksums {
+            sha256: sha2::Digest::finalize(self.sha256).into(),
+            md5: md5::Digest::finalize(self.md5).into(),
+        }
+    }
+}
+
+/// Uploads smaller than this finish too quickly to measure throughput.
+const MIN_MEASURED_SIZE: u64 = 1024 * 1024;
+
+/// Upload throughput measured on this machine, kept in `~/.aether` to
+/// estimate how long an upload takes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct UploadStats {
+    pub bytes_per_sec: f64,
+}
+
+impl UploadStats {
+    fn path() -> Result<PathBuf> {
+        Ok(Config::aether_dir()?.join("upload-stats.json"))
+    }
+
+    pub fn load() -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path().ok()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Fold an upload of `size` bytes that took `elapsed` into the measured
+    /// throughput. Failing to save it is not an error.
+    pub fn record(size: u64, elapsed: Duration) {
+        if size < MIN_MEASURED_SIZE || elapsed.is_zero() {
+            return;
+        }
+        let measured = size as f64 / elapsed.as_secs_f64();
+        // Average with earlier uploads so one slow upload does not dominate
+        let bytes_per_sec = match Self::load() {
+            Some(stats) => (stats.bytes_per_sec + measured) / 2.0,
+            None => measured,
+        };
+        let Ok(path) = Self::path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_vec(&Self { bytes_per_sec }) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+
+    /// Estimated time to upload `size` bytes, once an upload was measured.
+    pub fn estimate(size: u64) -> Option<Duration> {
+        Self::load()
+            .filter(|stats| stats.bytes_per_sec > 0.0)
+            .map(|stats| Duration::from_secs_f64(size as f64 / stats.bytes_per_sec))
+    }
+}
+
+/// Message reporting that an unchanged artifact of `size` bytes was reused
+/// instead of uploaded, with the time that saved when it can be estimated.
+pub fn reused_message(size: u64) -> String {
+    let saved = match UploadStats::estimate(size) {
+        Some(saved) if saved.as_secs() > 0 => format!(
+            ", saving about {}",
+            crate::utils::format_duration(saved.as_secs())
+        ),
+        _ => String::new(),
+    };
+    format!(
+        "♻️  Artifact unchanged, reusing it (skipped uploading {}{})",
+        crate::utils::format_size(size),
+        saved
+    )
+}
+
+/// A terminal bar showing bytes sent, throughput and time remaining,
+/// together with the callback that drives it.
+pub fn progress_bar(message: &str) -> (ProgressBar, ProgressCallback) {
+    // Nothing is drawn until the caller prints through the bar or progress
+    // is reported
+    let pb = ProgressBar::new(0)
+        .with_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{msg} [{bar:30.green/dim}] {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta})",
+                )
+                .unwrap()
+                .progress_chars("=> "),
+        )
+        .with_message(message.to_string());
+
+    let bar = pb.clone();
+    let callback: ProgressCallback = Arc::new(move |sent, total| {
+        bar.set_length(total);
+        bar.set_position(sent);
+    });
+    (pb, callback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_file_stream_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("artifact.tar.gz");
+        std::fs::write(&path, vec![7u8; CHUNK_SIZE * 2 + 10]).unwrap();
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let (total, stream) = file_stream(
+            &path,
+            Some(Arc::new(move |sent, total| {
+                sink.lock().unwrap().push((sent, total))
+            })),
+        )
+        .await
+        .unwrap();
+
+        let chunks: Vec<Bytes> = stream.try_collect().await.unwrap();
+        assert_eq!(total, CHUNK_SIZE as u64 * 2 + 10);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= CHUNK_SIZE));
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len() as u64).sum::<u64>(),
+            total
+        );
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.first(), Some(&(0, total)));
+        assert_eq!(reports.last(), Some(&(total, total)));
+    }
+
+    #[tokio::test]
+    async fn test_part_checksums() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("artifact.tar.gz");
+        let content: Vec<u8> = (0..CHUNK_SIZE * 3).map(|i| i as u8).collect();
+        std::fs::write(&path, &content).unwrap();
+
+        let part_size = CHUNK_SIZE as u64 + 100;
+        let (whole, parts) = Checksums::of_parts(&path, part_size).await.unwrap();
+        assert_eq!(whole, Checksums::of_file(&path).await.unwrap());
+        assert_eq!(
+            whole.sha256_hex(),
+            format!("{:x}", <Sha256 as sha2::Digest>::digest(&content))
+        );
+        assert_eq!(parts.len(), 3);
+        let mut last = ChecksumHasher::default();
+        last.update(&content[2 * part_size as usize..]);
+        assert_eq!(parts[2], last.finish());
+    }
+
+    #[tokio::test]
+    async fn test_download_checks_stored_sha256() {
+        use sha2::Digest;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/artifact.tar.gz", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 8\r\nConnection: close\r\n\r\nartifact")
+                    .await
+                    .unwrap();
+            }
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("artifact.tar.gz");
+        let sha256 = format!("{:x}", Sha256::digest(b"artifact"));
+
+        let error = download_file(&url, &path, Some(&"0".repeat(64)), None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("corrupted on the way"));
+        // Neither the file nor the partial download is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let checksums = download_file(&url, &path, Some(&sha256), None)
+            .await
+            .unwrap();
+        assert_eq!(checksums.sha256_hex(), sha256);
+        assert_eq!(std::fs::read(&path).unwrap(), b"artifact");
+    }
+}
@@ -69,6 +69,39 @@ pub fn format_duration(seconds: u64) -> String {
     }
 }
 
+/// Parse a duration such as `90s`, `2m` or `1h30m`; bare numbers are
+/// seconds.
+pub fn parse_duration(text: &str) -> std::result::Result<std::time::Duration, String> {
+    let invalid = || format!("invalid duration '{}' (expected e.g. 30s, 2m or 1h)", text);
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            _ => return Err(invalid()),
+        };
+        let part = number.parse::<u64>().map_err(|_| invalid())?;
+        seconds = part
+            .checked_mul(unit)
+            .and_then(|part| seconds.checked_add(part))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+    if !number.is_empty() {
+        let part = number.parse::<u64>().map_err(|_| invalid())?;
+        seconds = seconds.checked_add(part).ok_or_else(invalid)?;
+    } else if seconds == 0 && !text.trim().starts_with('0') {
+        return Err(invalid());
+    }
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
 pub fn confirm(message: &str) -> Result<bool> {
     use dialoguer::Confirm;
 
@@ -92,8 +125,8 @@ pub fn find_project_root(start_dir: &Path) -> Option<std::path::PathBuf> {
     let mut current = start_dir;
 
     loop {
-        // Check for package.json
-        if current.join("package.json").exists() {
+        // Check for package.json, pyproject.toml, ...
+        if crate::runtime::is_project_root(current) {
             return Some(current.to_path_buf());
         }
 
@@ -108,6 +141,16 @@ pub fn find_project_root(start_dir: &Path) -> Option<std::path::PathBuf> {
     None
 }
 
+/// Hex-encoded SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
 pub fn validate_app_name(name: &str) -> Result<()> {
     // Check if name is valid (lowercase, alphanumeric, hyphens)
     if name.is_empty() {
@@ -159,6 +202,23 @@ mod tests {
         assert_eq!(format_duration(3661), "1h 1m");
     }
 
+    #[test]
+    fn test_parse_duration() {
+        let secs = |text| parse_duration(text).map(|duration| duration.as_secs());
+        assert_eq!(secs("45"), Ok(45));
+        assert_eq!(secs("30s"), Ok(30));
+        assert_eq!(secs("2m"), Ok(120));
+        assert_eq!(secs("1h30m"), Ok(5400));
+        assert_eq!(secs("0s"), Ok(0));
+        assert!(secs("").is_err());
+        assert!(secs("m").is_err());
+        assert!(secs("2d").is_err());
+        // Too large for u64 seconds
+        assert!(secs("5124095576030432h").is_err());
+        assert!(secs("18446744073709551615s1s").is_err());
+        assert!(secs("18446744073709551615m").is_err());
+    }
+
     #[test]
     fn test_validate_app_name() {
         assert!(validate_app_name("my-app").is_ok());
@@ -0,0 +1,491 @@
+use crate::runtime::PackageJson;
+use crate::{AetherError, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// An npm, yarn or pnpm workspace and the packages it contains.
+#[derive(Debug)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<WorkspaceMember>,
+}
+
+#[derive(Debug)]
+pub struct WorkspaceMember {
+    pub name: String,
+    /// Location of the package relative to the workspace root
+    pub relative_path: PathBuf,
+    pub package_json: PackageJson,
+}
+
+#[derive(Debug, Deserialize)]
+struct PnpmWorkspace {
+    #[serde(default)]
+    packages: Vec<String>,
+}
+
+impl Workspace {
+    /// Walk up from `start_dir` to the nearest directory declaring a
+    /// workspace, either via `workspaces` in package.json or a
+    /// `pnpm-workspace.yaml`.
+    pub fn discover(start_dir: &Path) -> Result<Option<Self>> {
+        let mut current = Some(start_dir);
+
+        while let Some(dir) = current {
+            if let Some(patterns) = workspace_patterns(dir)? {
+                return Self::load(dir, &patterns).map(Some);
+            }
+            current = dir.parent();
+        }
+
+        Ok(None)
+    }
+
+    fn load(root: &Path, patterns: &[String]) -> Result<Self> {
+        let (excludes, includes): (Vec<&String>, Vec<&String>) =
+            patterns.iter().partition(|p| p.starts_with('!'));
+
+        let mut paths = Vec::new();
+        for pattern in includes {
+            expand_pattern(root, Path::new(""), &pattern_segments(pattern), &mut paths)?;
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut members = Vec::new();
+        for relative_path in paths {
+            let excluded = excludes
+                .iter()
+                .any(|exclude| glob_path_matches(&pattern_segments(&exclude[1..]), &relative_path));
+            let package_json_path = root.join(&relative_path).join("package.json");
+            if excluded || !package_json_path.is_file() {
+                continue;
+            }
+
+            let content = std::fs::read_to_string(&package_json_path)?;
+            let package_json: PackageJson = serde_json::from_str(&content)?;
+            members.push(WorkspaceMember {
+                name: package_json.name.clone(),
+                relative_path,
+                package_json,
+            });
+        }
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            members,
+        })
+    }
+
+    pub fn member(&self, name: &str) -> Option<&WorkspaceMember> {
+        self.members.iter().find(|member| member.name == name)
+    }
+
+    /// Workspace packages `name` depends on, directly or transitively,
+    /// ordered so that every package comes after its own dependencies.
+    pub fn local_dependencies(&self, name: &str) -> Vec<&WorkspaceMember> {
+        let mut ordered = Vec::new();
+        let mut visited = HashSet::new();
+        visited.insert(name.to_string());
+
+        if let Some(member) = self.member(name) {
+            self.visit_local_dependencies(member, &mut visited, &mut ordered);
+        }
+        ordered
+    }
+
+    fn visit_local_dependencies<'a>(
+        &'a self,
+        member: &'a WorkspaceMember,
+        visited: &mut HashSet<String>,
+        ordered: &mut Vec<&'a WorkspaceMember>,
+    ) {
+        for dependency in member.package_json.runtime_dependency_names() {
+            let Some(dependency) = self.member(dependency) else {
+                continue;
+            };
+            if visited.insert(dependency.name.clone()) {
+                self.visit_local_dependencies(dependency, visited, ordered);
+                ordered.push(dependency);
+            }
+        }
+    }
+
+    pub fn uses_pnpm(&self) -> bool {
+        self.root.join("pnpm-workspace.yaml").exists()
+    }
+}
+
+fn workspace_patterns(dir: &Path) -> Result<Option<Vec<String>>> {
+    let pnpm_workspace = dir.join("pnpm-workspace.yaml");
+    if pnpm_workspace.is_file() {
+        let content = std::fs::read_to_string(&pnpm_workspace)?;
+        let parsed: PnpmWorkspace = serde_yaml::from_str(&content).map_err(|e| {
+            AetherError::invalid_project(format!("Invalid pnpm-workspace.yaml: {}", e))
+        })?;
+        return Ok(Some(parsed.packages));
+    }
+
+    let package_json = dir.join("package.json");
+    if package_json.is_file() {
+        let content = std::fs::read_to_string(&package_json)?;
+        let value: serde_json::Value = serde_json::from_str(&content)?;
+        let workspaces = match value.get("workspaces") {
+            Some(serde_json::Value::Array(patterns)) => Some(patterns),
+            Some(serde_json::Value::Object(config)) => {
+                config.get("packages").and_then(|p| p.as_array())
+            }
+            _ => None,
+        };
+        if let Some(patterns) = workspaces {
+            return Ok(Some(
+                patterns
+                    .iter()
+                    .filter_map(|p| p.as_str().map(str::to_string))
+                    .collect(),
+            ));
+        }
+    }
+
+    Ok(None)
+}
+
+fn pattern_segments(pattern: &str) -> Vec<&str> {
+    pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect()
+}
+
+/// Collect the directories under `root` matching a workspace glob such as
+/// `packages/*` or `apps/**`.
+fn expand_pattern(
+    root: &Path,
+    prefix: &Path,
+    segments: &[&str],
+    matches: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        matches.push(prefix.to_path_buf());
+        return Ok(());
+    };
+
+    if *segment == "**" {
+        // Zero directories...
+        expand_pattern(root, prefix, rest, matches)?;
+        // ...or one more level, keeping `**` active
+        for child in child_dirs(&root.join(prefix))? {
+            expand_pattern(root, &prefix.join(child), segments, matches)?;
+        }
+    } else if segment.contains('*') {
+        for child in child_dirs(&root.join(prefix))? {
+            if wildcard_matches(segment, &child) {
+                expand_pattern(root, &prefix.join(child), rest, matches)?;
+            }
+        }
+    } else if root.join(prefix).join(segment).is_dir() {
+        expand_pattern(root, &prefix.join(segment), rest, matches)?;
+    }
+
+    Ok(())
+}
+
+fn child_dirs(dir: &Path) -> Result<Vec<String>> {
+    let mut children = Vec::new();
+    if !dir.is_dir() {
+        return Ok(children);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_dir() && name != "node_modules" && !name.starts_with('.') {
+            children.push(name);
+        }
+    }
+    children.sort();
+    Ok(children)
+}
+
+fn glob_path_matches(segments: &[&str], path: &Path) -> bool {
+    let components: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    glob_components_match(segments, &components)
+}
+
+fn glob_components_match(segments: &[&str], components: &[String]) -> bool {
+    match (segments.split_first(), components.split_first()) {
+        (None, None) => true,
+        (Some((&"**", rest)), _) => {
+            glob_components_match(rest, components)
+                || (!components.is_empty() && glob_components_match(segments, &components[1..]))
+        }
+        (Some((segment, rest)), Some((component, remaining))) => {
+            wildcard_matches(segment, component) && glob_components_match(rest, remaining)
+        }
+        _ => false,
+    }
+}
+
+/// Match a single path segment against a pattern where `*` matches any run
+/// of characters.
+fn wildcard_matches(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !name.starts_with(first) || name.len() < first.len() + last.len() {
+        return false;
+    }
+
+    let mut remaining = &name[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    remaining.ends_with(last)
+}
+
+/// Copy the production dependency closure of the package at `package_dir`
+/// into `bundle_dir/node_modules`, resolving each dependency the way Node
+/// does (nearest `node_modules`, walking up to `workspace_root`). Hoisted
+/// dependencies and workspace packages are both picked up this way.
+pub fn bundle_production_dependencies(
+    package_dir: &Path,
+    workspace_root: &Path,
+    bundle_dir: &Path,
+) -> Result<usize> {
+    let mut bundle = DependencyBundle {
+        workspace_root: workspace_root.canonicalize()?,
+        placed: BTreeMap::new(),
+        visited: HashSet::new(),
+    };
+
+    let package_json = read_package_json(package_dir)?;
+    bundle.visit(&package_dir.canonicalize()?, &package_json, Path::new(""))?;
+
+    for (destination, source) in &bundle.placed {
+        crate::builder::copy_dir_excluding(
+            source,
+            &bundle_dir.join(destination),
+            &["node_modules", ".git"],
+        )?;
+    }
+
+    Ok(bundle.placed.len())
+}
+
+struct DependencyBundle {
+    workspace_root: PathBuf,
+    /// Destination inside the bundle (e.g. `node_modules/foo`) to the
+    /// resolved source directory
+    placed: BTreeMap<PathBuf, PathBuf>,
+    visited: HashSet<(PathBuf, PathBuf)>,
+}
+
+impl DependencyBundle {
+    fn visit(&mut self, from_dir: &Path, package_json: &PackageJson, dest: &Path) -> Result<()> {
+        // Place every dependency of this package before walking into any of
+        // them, so a package's own dependencies take the top-level slots
+        // ahead of dependencies further down the tree
+        let mut dependencies = Vec::new();
+        for (name, required) in package_json.runtime_dependencies() {
+            let Some(resolved) = self.resolve(name, from_dir) else {
+                if required {
+                    return Err(AetherError::build(format!(
+                        "Dependency '{}' of '{}' is not installed",
+                        name, package_json.name
+                    )));
+                }
+                continue;
+            };
+
+            let top_level = Path::new("node_modules").join(name);
+            let destination = match self.placed.get(&top_level) {
+                Some(existing) if *existing == resolved => continue,
+                // A different version is already hoisted; nest this one
+                Some(_) => dest.join("node_modules").join(name),
+                None => top_level,
+            };
+
+            if !self.visited.insert((resolved.clone(), destination.clone())) {
+                continue;
+            }
+            self.placed.insert(destination.clone(), resolved.clone());
+            dependencies.push((resolved, destination));
+        }
+
+        for (resolved, destination) in dependencies {
+            let dependency_json = read_package_json(&resolved)?;
+            self.visit(&resolved, &dependency_json, &destination)?;
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&self, name: &str, from_dir: &Path) -> Option<PathBuf> {
+        let mut current = Some(from_dir);
+        while let Some(dir) = current {
+            let candidate = dir.join("node_modules").join(name);
+            if candidate.join("package.json").is_file() {
+                return candidate.canonicalize().ok();
+            }
+            if dir == self.workspace_root {
+                break;
+            }
+            current = dir.parent();
+        }
+        None
+    }
+}
+
+fn read_package_json(dir: &Path) -> Result<PackageJson> {
+    let content = std::fs::read_to_string(dir.join("package.json"))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_wildcard_matches() {
+        assert!(wildcard_matches("*", "api"));
+        assert!(wildcard_matches("svc-*", "svc-api"));
+        assert!(wildcard_matches("*-app", "web-app"));
+        assert!(!wildcard_matches("svc-*", "web"));
+        assert!(!wildcard_matches("api", "apis"));
+    }
+
+    #[test]
+    fn test_discover_and_local_dependencies() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            &root.path().join("package.json"),
+            r#"{"name":"root","workspaces":["packages/*","!packages/ignored"]}"#,
+        );
+        write(
+            &root.path().join("packages/api/package.json"),
+            r#"{"name":"api","dependencies":{"shared":"*","express":"^4"}}"#,
+        );
+        write(
+            &root.path().join("packages/shared/package.json"),
+            r#"{"name":"shared","dependencies":{"utils":"workspace:*"}}"#,
+        );
+        write(
+            &root.path().join("packages/utils/package.json"),
+            r#"{"name":"utils"}"#,
+        );
+        write(
+            &root.path().join("packages/ignored/package.json"),
+            r#"{"name":"ignored"}"#,
+        );
+
+        let workspace = Workspace::discover(&root.path().join("packages/api"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(workspace.root, root.path());
+        assert_eq!(workspace.members.len(), 3);
+        assert!(workspace.member("ignored").is_none());
+
+        let local: Vec<&str> = workspace
+            .local_dependencies("api")
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect();
+        assert_eq!(local, vec!["utils", "shared"]);
+    }
+
+    #[test]
+    fn test_bundle_hoisted_and_nested_dependencies() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        write(
+            &root.join("package.json"),
+            r#"{"name":"root","workspaces":["app"]}"#,
+        );
+        write(
+            &root.join("app/package.json"),
+            r#"{"name":"app","dependencies":{"a":"1","b":"1"}}"#,
+        );
+        write(
+            &root.join("node_modules/a/package.json"),
+            r#"{"name":"a","version":"1.0.0","dependencies":{"c":"1"}}"#,
+        );
+        write(
+            &root.join("node_modules/b/package.json"),
+            r#"{"name":"b","version":"1.0.0","dependencies":{"c":"2"}}"#,
+        );
+        write(
+            &root.join("node_modules/b/node_modules/c/package.json"),
+            r#"{"name":"c","version":"2.0.0"}"#,
+        );
+        write(
+            &root.join("node_modules/c/package.json"),
+            r#"{"name":"c","version":"1.0.0"}"#,
+        );
+        write(
+            &root.join("node_modules/unused/package.json"),
+            r#"{"name":"unused"}"#,
+        );
+
+        let bundle = tempfile::tempdir().unwrap();
+        let count = bundle_production_dependencies(&root.join("app"), root, bundle.path()).unwrap();
+
+        assert_eq!(count, 4);
+        let version = |path: &str| {
+            let json = read_package_json(&bundle.path().join(path)).unwrap();
+            json.version.unwrap()
+        };
+        assert_eq!(version("node_modules/c"), "1.0.0");
+        assert_eq!(version("node_modules/b/node_modules/c"), "2.0.0");
+        assert!(!bundle.path().join("node_modules/unused").exists());
+    }
+
+    #[test]
+    fn test_bundle_direct_dependency_wins_top_level() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        write(
+            &root.join("package.json"),
+            r#"{"name":"app","dependencies":{"a":"1","c":"2"}}"#,
+        );
+        // `a` is walked first and needs another version of `c`
+        write(
+            &root.join("node_modules/a/package.json"),
+            r#"{"name":"a","version":"1.0.0","dependencies":{"c":"1"}}"#,
+        );
+        write(
+            &root.join("node_modules/a/node_modules/c/package.json"),
+            r#"{"name":"c","version":"1.0.0"}"#,
+        );
+        write(
+            &root.join("node_modules/c/package.json"),
+            r#"{"name":"c","version":"2.0.0"}"#,
+        );
+
+        let bundle = tempfile::tempdir().unwrap();
+        let count = bundle_production_dependencies(root, root, bundle.path()).unwrap();
+
+        assert_eq!(count, 3);
+        let version = |path: &str| {
+            let json = read_package_json(&bundle.path().join(path)).unwrap();
+            json.version.unwrap()
+        };
+        assert_eq!(version("node_modules/c"), "2.0.0");
+        assert_eq!(version("node_modules/a/node_modules/c"), "1.0.0");
+    }
+}